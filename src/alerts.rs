@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use iced::{Alignment, Element, Task};
+use iced::widget::{button, text, Column, Row};
+use zbus::zvariant::Value;
+
+use crate::styles::*;
+use crate::time::format_duration;
+use crate::{Message, ToDo};
+
+// Which thresholds have already fired, so each one only alerts once
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Fired {
+    pub break_alert: bool,
+    pub long_task: bool,
+    // When due dates were last checked, only those passed since then remind
+    pub due_checked: Option<NaiveDateTime>,
+}
+
+// Sends a notification through the freedesktop notification service on the session bus,
+// DBUS_SESSION_BUS_ADDRESS decides which bus that is
+pub async fn notify(summary: String, body: String) -> Result<(), String> {
+    let connection = zbus::Connection::session().await.map_err(|error| error.to_string())?;
+    send_notification(&connection, &summary, &body).await
+}
+
+// The Notify call itself, on whatever connection it is given
+pub async fn send_notification(connection: &zbus::Connection, summary: &str, body: &str) -> Result<(), String> {
+    let actions: Vec<&str> = Vec::new();
+    let hints: HashMap<&str, Value<'_>> = HashMap::new();
+    connection.call_method(
+        Some("org.freedesktop.Notifications"),
+        "/org/freedesktop/Notifications",
+        Some("org.freedesktop.Notifications"),
+        "Notify",
+        &("To Do List", 0u32, "", summary, body, actions, hints, -1i32),
+    ).await.map_err(|error| error.to_string())?;
+    Ok(())
+}
+
+impl ToDo {
+    // Shows a banner and, when enabled, a desktop notification
+    pub fn alert(&mut self, summary: &str, body: String) -> Task<Message> {
+        self.alerts.push(format!("{}: {}", summary, body));
+        if self.config.desktop_notifications {
+            Task::perform(notify(summary.to_owned(), body), Message::Notified)
+        } else {
+            Task::none()
+        }
+    }
+
+    // Checked every Tick against the thresholds in the config
+    pub fn check_alerts(&mut self) -> Task<Message> {
+        let alerts = self.crossed_thresholds();
+        Task::batch(alerts.into_iter().map(|(summary, body)| self.alert(summary, body)).collect::<Vec<_>>())
+    }
+
+    // Thresholds crossed since the last check, each marked as fired, 0 minutes turns a threshold off
+    pub fn crossed_thresholds(&mut self) -> Vec<(&'static str, String)> {
+        let mut alerts = Vec::new();
+
+        let break_limit = Duration::from_secs(u64::from(self.config.break_alert_minutes) * 60);
+        if !self.rest {
+            self.fired.break_alert = false;
+        } else if !break_limit.is_zero() && !self.fired.break_alert && self.break_time.current() >= break_limit {
+            self.fired.break_alert = true;
+            alerts.push(("Long break", format!("Your break has gone on for over {}", format_duration(break_limit))));
+        }
+
+        let task_limit = Duration::from_secs(u64::from(self.config.task_alert_minutes) * 60);
+        // Like breaks, only the current stretch counts, time from earlier sessions doesn't
+        let long_task = self.active.filter(|_| !task_limit.is_zero())
+            .and_then(|active| self.all_tasks().find(|task| task.id == active && task.time.current() >= task_limit))
+            .map(|task| format!("\"{}\" has been running for over {}", task.title, format_duration(task_limit)));
+        match long_task {
+            None => self.fired.long_task = false,
+            Some(body) if !self.fired.long_task => {
+                self.fired.long_task = true;
+                alerts.push(("Long task", body));
+            },
+            Some(_) => {},
+        }
+
+        // Each due date reminds once, when its moment arrives, so dates already past at launch or after a load stay quiet
+        let now = self.time.naive_local();
+        if let Some(since) = self.fired.due_checked.replace(now) {
+            alerts.extend(self.all_tasks()
+                .filter_map(|task| task.due.filter(|due| since < due.moment() && due.moment() <= now).map(|due| ("Task due", format!("\"{}\" is due {}", task.title, due)))));
+        }
+        alerts
+    }
+
+    pub fn alerts_view(&self) -> Option<Element<'_, Message>> {
+        if self.alerts.is_empty() {
+            return None;
+        }
+        let mut alerts: Column<'_, Message> = Column::new().spacing(5);
+        for (index, alert) in self.alerts.iter().enumerate() {
+            alerts = alerts.push(
+                Row::new()
+                    .push(text(alert).size(16).color(get_rgb_color(255, 140, 0)))
+                    .push(button("Dismiss").on_press(Message::DismissAlert(index)))
+                    .spacing(10).align_y(Alignment::Center)
+            );
+        }
+        Some(alerts.into())
+    }
+}
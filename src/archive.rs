@@ -0,0 +1,106 @@
+use chrono::NaiveDate;
+use iced::{Alignment, Color, Element, Length, Theme};
+use iced::widget::{button, horizontal_space, text, text_input, Column, Container, Row, Scrollable};
+
+use crate::styles::buttons::*;
+use crate::styles::*;
+use crate::task::{Task, TaskStatus};
+use crate::{Message, Screen, ToDo};
+
+impl ToDo {
+    // Moves a finished task from the shown list into the archive, newest first
+    pub fn archive_task(&mut self, mut task: Task) {
+        task.list = Some(self.lists[self.current_list].name.clone());
+        self.archive.insert(0, task);
+    }
+
+    // Takes a task back out of the archive and puts it at the end of the list it came from,
+    // or of the shown list when that one is gone
+    pub fn restore_task(&mut self, id: u64) {
+        let Some(index) = self.archive.iter().position(|task| task.id == id) else {return};
+        let mut task = self.archive.remove(index);
+        let from = task.list.take().and_then(|name| self.lists.iter().position(|list| list.name == name)).filter(|index| *index != self.current_list);
+        // Keep the new task input as the last element while adding
+        let adding = self.add && from.is_none();
+        let (tasks, complete, removed) = match from {
+            Some(index) => {
+                let list = &mut self.lists[index];
+                (&mut list.tasks, &mut list.complete, &mut list.removed)
+            },
+            None => (&mut self.tasks, &mut self.complete, &mut self.removed),
+        };
+        match task.status {
+            TaskStatus::Completed => *complete = complete.saturating_sub(1),
+            TaskStatus::Removed => *removed = removed.saturating_sub(1),
+            TaskStatus::Active => {},
+        }
+        task.status = TaskStatus::Active;
+        task.completed = None;
+        if adding {
+            tasks.insert(tasks.len() - 1, task);
+        } else {
+            tasks.push(task);
+        }
+    }
+
+    pub fn archive_view(&self) -> Element<'_, Message> {
+        let radius = 2;
+        let mut main: Column<'_, Message> = Column::new().align_x(Alignment::Center).width(Length::Fill).padding(20).spacing(10);
+
+        main = main.push(
+            Row::new()
+                .push(button("Back").on_press(Message::Show(Screen::Tasks)))
+                .push(horizontal_space())
+                .push(text_input("Filter by date (dd/mm/yyyy)", &self.archive_filter)
+                    .on_input(Message::ArchiveFilter)
+                    .width(Length::Fixed(240.0)))
+                .spacing(10)
+        );
+        main = main.push(text("Archive: ").size(20));
+
+        // An empty filter shows everything, an unparseable one shows nothing
+        let filter = if self.archive_filter.trim().is_empty() {
+            None
+        } else {
+            match NaiveDate::parse_from_str(self.archive_filter.trim(), "%d/%m/%Y") {
+                Ok(date) => Some(date),
+                Err(_) => {
+                    main = main.push(text("Enter a date as dd/mm/yyyy").size(14));
+                    return main.into();
+                }
+            }
+        };
+
+        let mut tasks: Column<'_, Message> = Column::new().align_x(Alignment::Center).width(Length::Fill).padding(20).spacing(10);
+        for task in self.archive.iter().filter(|task| filter.is_none() || task.completed.map(|time| time.date_naive()) == filter) {
+            let finished = task.completed.map(|time| time.format("%d/%m/%Y %H:%M:%S").to_string()).unwrap_or_default();
+            tasks = tasks.push(
+                Container::new(
+                    Row::new()
+                        .push(text(if task.status == TaskStatus::Completed {"Completed"} else {"Removed"}).size(14)
+                            .color(if task.status == TaskStatus::Completed {get_rgb_color(0, 155, 0)} else {get_rgb_color(220, 8, 51)}))
+                        .push(text(&task.title).size(16))
+                        .push_maybe(task.list.as_ref().filter(|_| self.lists.len() > 1).map(|list| text(format!("in {}", list)).size(14)))
+                        .push(horizontal_space())
+                        .push(text(finished).size(14))
+                        .push(text(task.time.to_string()).size(16))
+                        .push(button("Restore").on_press(Message::Restore(task.id)).style(
+                            move |_: &Theme, status| {
+                                match status {
+                                    button::Status::Hovered => {
+                                        style_button(get_rgb_color(0, 180, 0), Color::WHITE, radius)
+                                    }
+                                    _ => {
+                                        style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
+                                    }
+                                }
+                            }
+                        ))
+                        .spacing(20).width(Length::Fill).align_y(Alignment::Center)
+                )
+            );
+        }
+        main = main.push(Scrollable::new(tasks));
+        main.into()
+    }
+}
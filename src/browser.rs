@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+
+use iced::{Alignment, Color, Element, Length, Task, Theme};
+use iced::widget::{button, horizontal_space, text, text_input, Column, Row, Scrollable};
+
+use crate::fs::{is_json, list_backups, list_directory, read_from_file, Backup, Listing};
+use crate::styles::buttons::*;
+use crate::styles::*;
+use crate::{Message, Screen, ToDo};
+
+// Most recently used files kept in the recent list
+const RECENT_LIMIT: usize = 8;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Mode {
+    Open,
+    SaveAs,
+}
+
+// State of the in-app file browser used by Open and Save As
+#[derive(Debug, PartialEq, Clone)]
+pub struct Browser {
+    pub mode: Mode,
+    pub directory: String,
+    pub file_name: String,
+    pub listing: Listing,
+    pub backups: Vec<Backup>,
+    pub error: Option<String>,
+}
+
+impl Browser {
+    pub fn new(directory: &Path) -> Self {
+        Self {
+            mode: Mode::Open,
+            directory: directory.display().to_string(),
+            file_name: String::new(),
+            listing: Listing::default(),
+            backups: Vec::new(),
+            error: None,
+        }
+    }
+
+    // Full path of the file Save As will write, always with a .json extension
+    fn target(&self) -> Option<PathBuf> {
+        let name = self.file_name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let path = Path::new(&self.directory).join(name);
+        Some(if is_json(&path) {path} else {path.with_extension("json")})
+    }
+}
+
+impl ToDo {
+    pub fn title(&self) -> String {
+        match &self.current_file {
+            Some(path) => format!("To Do List - {}", path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default()),
+            None => "To Do List".to_owned(),
+        }
+    }
+
+    // Moves a file to the front of the recent list and stores it in the config
+    pub fn push_recent(&mut self, path: PathBuf) -> Task<Message> {
+        let recent_files = &mut self.config.recent_files;
+        recent_files.retain(|recent| recent != &path);
+        recent_files.insert(0, path);
+        recent_files.truncate(RECENT_LIMIT);
+        self.store_config()
+    }
+
+    pub fn open_browser(&mut self, mode: Mode) -> Task<Message> {
+        self.browser.mode = mode;
+        self.browser.error = None;
+        if mode == Mode::SaveAs && let Some(name) = self.current_file.as_ref().and_then(|path| path.file_name()) {
+            self.browser.file_name = name.to_string_lossy().into_owned();
+        }
+        self.screen = Screen::Files;
+        if mode == Mode::Open {
+            let backups = Task::perform(list_backups(self.backups_dir()), |result| Message::BackupsListed(result.map_err(|error| error.kind())));
+            Task::batch([self.refresh_browser(), backups])
+        } else {
+            self.refresh_browser()
+        }
+    }
+
+    pub fn refresh_browser(&self) -> Task<Message> {
+        Task::perform(list_directory(PathBuf::from(&self.browser.directory)), Message::Listed)
+    }
+
+    pub fn browse_to(&mut self, directory: PathBuf) -> Task<Message> {
+        self.browser.directory = directory.display().to_string();
+        self.refresh_browser()
+    }
+
+    pub fn pick_file(&mut self, path: PathBuf) -> Task<Message> {
+        match self.browser.mode {
+            Mode::Open => {
+                self.screen = Screen::Tasks;
+                Task::perform(read_from_file(path), Message::FileOpen)
+            },
+            Mode::SaveAs => {
+                self.browser.file_name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                Task::none()
+            },
+        }
+    }
+
+    pub fn confirm_save_as(&mut self) -> Task<Message> {
+        match self.browser.target() {
+            Some(path) => {
+                self.screen = Screen::Tasks;
+                self.save_to(path, Message::FileSave)
+            },
+            None => {
+                self.browser.error = Some("Enter a file name".to_owned());
+                Task::none()
+            },
+        }
+    }
+
+    pub fn browser_view(&self) -> Element<'_, Message> {
+        let radius = 2;
+        let browser = &self.browser;
+        let mut main: Column<'_, Message> = Column::new().align_x(Alignment::Center).width(Length::Fill).padding(20).spacing(10);
+
+        main = main.push(
+            Row::new()
+                .push(button("Back").on_press(Message::Show(Screen::Tasks)))
+                .push(text(if browser.mode == Mode::Open {"Open"} else {"Save As"}).size(20))
+                .push(horizontal_space())
+                .spacing(10).align_y(Alignment::Center)
+        );
+        main = main.push(
+            Row::new()
+                .push(button("Up").on_press_maybe(Path::new(&browser.directory).parent().map(|parent| Message::BrowseTo(parent.to_path_buf()))))
+                .push(text_input("Directory", &browser.directory)
+                    .on_input(Message::BrowseDirectory)
+                    .on_submit(Message::Refresh))
+                .push(button("Go").on_press(Message::Refresh))
+                .spacing(10).align_y(Alignment::Center)
+        );
+        if browser.mode == Mode::SaveAs {
+            main = main.push(
+                Row::new()
+                    .push(text_input("File name", &browser.file_name)
+                        .on_input(Message::FileName)
+                        .on_submit(Message::ConfirmSaveAs))
+                    .push(button("Save").on_press(Message::ConfirmSaveAs).style(
+                        move |_: &Theme, status| {
+                            match status {
+                                button::Status::Hovered => {
+                                    style_button(get_rgb_color(0, 180, 0), Color::WHITE, radius)
+                                }
+                                _ => {
+                                    style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
+                                }
+                            }
+                        }
+                    ))
+                    .spacing(10).align_y(Alignment::Center)
+            );
+        }
+        if let Some(error) = &browser.error {
+            main = main.push(text(error).size(14).color(get_rgb_color(220, 8, 51)));
+        }
+
+        let mut entries: Column<'_, Message> = Column::new().width(Length::Fill).spacing(5);
+        for dir in browser.listing.dirs.iter() {
+            let name = dir.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            entries = entries.push(button(text(format!("{}/", name))).on_press(Message::BrowseTo(dir.clone())).style(button::text));
+        }
+        for file in browser.listing.files.iter() {
+            let name = file.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            entries = entries.push(button(text(name)).on_press(Message::PickFile(file.clone())).style(button::secondary));
+        }
+        if browser.listing.dirs.is_empty() && browser.listing.files.is_empty() {
+            entries = entries.push(text("No .json files here").size(14));
+        }
+        main = main.push(Scrollable::new(entries).height(Length::Fill));
+
+        if browser.mode == Mode::Open && !self.config.recent_files.is_empty() {
+            main = main.push(text("Recent Files: ").size(16));
+            for recent in self.config.recent_files.iter() {
+                main = main.push(button(text(recent.display().to_string()).size(14)).on_press(Message::PickFile(recent.clone())).style(button::text));
+            }
+        }
+        if browser.mode == Mode::Open && !browser.backups.is_empty() {
+            main = main.push(text("Restore from Backup: ").size(16));
+            let mut backups: Column<'_, Message> = Column::new().width(Length::Fill).spacing(5);
+            for backup in browser.backups.iter() {
+                let name = backup.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                // Saves with the same name are told apart by where they live
+                let label = match &backup.source {
+                    Some(source) => format!("{}  of  {}", name, source.display()),
+                    None => name,
+                };
+                backups = backups.push(button(text(label).size(14)).on_press(Message::PickFile(backup.path.clone())).style(button::text));
+            }
+            main = main.push(Scrollable::new(backups).height(Length::Fixed(150.0)));
+        }
+        main.into()
+    }
+}
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use crate::report::{Format, Period};
+
+pub const USAGE: &str = "Usage: todo-list [--data-dir <path>] [--report day|week [options]]
+
+Options:
+    --data-dir <path>    Directory for saves and config.json
+    --report day|week    Print a timesheet instead of opening the window
+    --date <dd/mm/yyyy>  Day the report covers, or a day in its week (default today)
+    --format csv|md|html Report format (default md)
+    --file <path>        Save to report on (default the most recent file)
+    --output <path>      Write the report to a file instead of printing it
+    -h, --help           Print this help
+
+Environment:
+    TODO_LIST_DATA_DIR   Data directory used when --data-dir is not given";
+
+// Options given on the command line
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Args {
+    pub data_dir: Option<PathBuf>,
+    pub help: bool,
+    pub report: Option<Period>,
+    pub date: Option<String>,
+    pub format: Option<Format>,
+    pub file: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+}
+
+// Flags written as --flag value or --flag=value
+fn value(arg: &str, flag: &str, args: &mut impl Iterator<Item = String>) -> Result<Option<String>, String> {
+    if arg == flag {
+        args.next().map(Some).ok_or(format!("{} needs a value", flag))
+    } else {
+        Ok(arg.strip_prefix(flag).and_then(|rest| rest.strip_prefix('=')).map(str::to_owned))
+    }
+}
+
+impl Args {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                parsed.help = true;
+            } else if let Some(dir) = value(&arg, "--data-dir", &mut args)? {
+                parsed.data_dir = Some(PathBuf::from(dir));
+            } else if let Some(period) = value(&arg, "--report", &mut args)? {
+                parsed.report = Some(Period::parse(&period).ok_or(format!("Unknown report period: {}", period))?);
+            } else if let Some(date) = value(&arg, "--date", &mut args)? {
+                parsed.date = Some(date);
+            } else if let Some(format) = value(&arg, "--format", &mut args)? {
+                parsed.format = Some(Format::parse(&format).ok_or(format!("Unknown report format: {}", format))?);
+            } else if let Some(file) = value(&arg, "--file", &mut args)? {
+                parsed.file = Some(PathBuf::from(file));
+            } else if let Some(output) = value(&arg, "--output", &mut args)? {
+                parsed.output = Some(PathBuf::from(output));
+            } else {
+                return Err(format!("Unknown argument: {}", arg));
+            }
+        }
+        Ok(parsed)
+    }
+}
@@ -0,0 +1,116 @@
+use chrono::format::{Item, StrftimeItems};
+use serde::{Deserialize, Serialize};
+use std::{io, path::{Path, PathBuf}};
+
+use crate::fs::write_atomic;
+
+// Overrides the platform data directory, the --data-dir flag takes precedence over it
+pub const DATA_DIR_VAR: &str = "TODO_LIST_DATA_DIR";
+
+const CONFIG_FILE: &str = "config.json";
+
+pub const DEFAULT_CLOCK_FORMAT: &str = "%d/%m/%Y %H:%M:%S";
+
+// Settings that live outside of the task data, stored as config.json in the data directory
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub auto_save: bool,
+    // Seconds between auto-saves
+    pub auto_save_interval: u32,
+    pub recent_files: Vec<PathBuf>,
+    // Saves as soon as a task is completed, on top of the interval
+    pub save_on_complete: bool,
+    // chrono format string for the clock
+    pub clock_format: String,
+    pub twelve_hour: bool,
+    // Whether the active task stops counting while on a break
+    pub break_pauses_task: bool,
+    // Copies of overwritten saves kept in the backups directory, 0 turns them off
+    pub backup_count: u32,
+    // Pomodoro phase lengths in minutes
+    pub pomodoro_work: u32,
+    pub pomodoro_short_break: u32,
+    pub pomodoro_long_break: u32,
+    // Work phases between long breaks
+    pub pomodoro_long_every: u32,
+    // Alert thresholds in minutes, 0 turns them off
+    pub break_alert_minutes: u32,
+    pub task_alert_minutes: u32,
+    // Also send alerts to the desktop over D-Bus
+    pub desktop_notifications: bool,
+    // Minutes without window events before the task timer pauses, 0 turns it off
+    pub idle_minutes: u32,
+    // Shows high and urgent tasks above the rest whatever the sort
+    pub pin_high_priority: bool,
+    // Open subtasks stop the parent being completed, otherwise they are completed along with it
+    pub subtasks_block_completion: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            auto_save: false,
+            auto_save_interval: 120,
+            recent_files: Vec::new(),
+            save_on_complete: false,
+            clock_format: DEFAULT_CLOCK_FORMAT.to_owned(),
+            twelve_hour: false,
+            break_pauses_task: true,
+            backup_count: 5,
+            pomodoro_work: 25,
+            pomodoro_short_break: 5,
+            pomodoro_long_break: 15,
+            pomodoro_long_every: 4,
+            break_alert_minutes: 15,
+            task_alert_minutes: 90,
+            desktop_notifications: false,
+            idle_minutes: 10,
+            pin_high_priority: false,
+            subtasks_block_completion: true,
+        }
+    }
+}
+
+impl Config {
+    // Reads the config from the data directory, a missing or broken file falls back to the defaults
+    pub fn load(data_dir: &Path) -> Self {
+        let path = data_dir.join(CONFIG_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|error| {
+                eprintln!("Ignoring invalid config {}: {}", path.display(), error);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    // The clock format with the hour swapped for a 12 hour one when needed
+    pub fn clock_pattern(&self) -> String {
+        let format = if valid_time_format(&self.clock_format) {self.clock_format.as_str()} else {DEFAULT_CLOCK_FORMAT};
+        if self.twelve_hour {
+            let format = format.replace("%H", "%I").replace("%k", "%l").replace("%R", "%I:%M").replace("%T", "%I:%M:%S");
+            if format.contains("%p") || format.contains("%P") {format} else {format + " %p"}
+        } else {
+            format.to_owned()
+        }
+    }
+}
+
+// chrono panics when displaying an invalid format, so check them before use
+pub fn valid_time_format(format: &str) -> bool {
+    !format.is_empty() && !StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+}
+
+pub async fn save_config(data_dir: PathBuf, config: Config) -> Result<(), io::ErrorKind> {
+    tokio::fs::create_dir_all(&data_dir).await.map_err(|error| error.kind())?;
+    let contents = serde_json::to_string_pretty(&config).map_err(|_| io::ErrorKind::InvalidData)?;
+    write_atomic(&data_dir.join(CONFIG_FILE), contents.as_bytes()).await.map_err(|error| error.kind())
+}
+
+// Picks the data directory: command line flag, then environment variable, then the platform default
+pub fn resolve_data_dir(flag: Option<PathBuf>) -> PathBuf {
+    flag.or_else(|| std::env::var_os(DATA_DIR_VAR).filter(|dir| !dir.is_empty()).map(PathBuf::from))
+        .or_else(|| dirs::data_dir().map(|dir| dir.join("todo-list")))
+        .unwrap_or_else(|| PathBuf::from("todo-list"))
+}
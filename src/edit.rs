@@ -0,0 +1,115 @@
+use iced::{event, keyboard, Alignment, Element, Event, Length, Task};
+use iced::widget::{button, pick_list, text, text_input, Row};
+
+use crate::styles::*;
+use crate::lists::parse_tags;
+use crate::recur::Recurrence;
+use crate::task::{Due, Priority};
+use crate::{Message, ToDo};
+
+// Task whose title is being changed, with the text typed so far
+#[derive(Debug, PartialEq, Clone)]
+pub struct Editing {
+    pub id: u64,
+    pub title: String,
+    pub priority: Priority,
+    pub due: String,
+    pub repeat: String,
+    pub tags: String,
+}
+
+// An empty due date clears it, anything else has to parse
+pub fn parse_due(value: &str) -> Option<Option<Due>> {
+    if value.trim().is_empty() {Some(None)} else {Due::parse(value).map(Some)}
+}
+
+// Same for recurrence rules
+pub fn parse_repeat(value: &str) -> Option<Option<Recurrence>> {
+    if value.trim().is_empty() {Some(None)} else {Recurrence::parse(value).map(Some)}
+}
+
+pub const REPEAT_HINT: &str = "Repeat daily, weekdays, weekly mon,thu, monthly, monthly on 15 or every 3 days";
+
+pub fn input_id() -> text_input::Id {
+    text_input::Id::new("edit-task")
+}
+
+// Escape is captured by focused inputs, so it is picked up whatever the event status
+pub fn escape(event: Event, _status: event::Status, _window: iced::window::Id) -> Option<Message> {
+    match event {
+        Event::Keyboard(keyboard::Event::KeyPressed { key: keyboard::Key::Named(keyboard::key::Named::Escape), .. }) => Some(Message::Cancel),
+        _ => None,
+    }
+}
+
+impl ToDo {
+    // Turns a task's title into an input, focused so typing can start straight away
+    pub fn start_edit(&mut self, id: u64) -> Task<Message> {
+        let Some(task) = self.tasks.iter().find(|task| task.id == id) else {return Task::none()};
+        self.focused = Some(id);
+        self.editing = Some(Editing {
+            id,
+            title: task.title.clone(),
+            priority: task.priority,
+            due: task.due.map(|due| due.to_string()).unwrap_or_default(),
+            repeat: task.recurrence.as_ref().map(|recurrence| recurrence.to_string()).unwrap_or_default(),
+            tags: task.tags.join(", "),
+        });
+        text_input::focus(input_id())
+    }
+
+    // Empty titles and unreadable due dates or recurrences are rejected and leave the input open
+    pub fn confirm_edit(&mut self) {
+        let Some(editing) = &self.editing else {return};
+        let title = editing.title.trim().to_owned();
+        let Some(due) = parse_due(&editing.due) else {return};
+        let Some(recurrence) = parse_repeat(&editing.repeat) else {return};
+        if title.is_empty() {
+            return;
+        }
+        let (id, priority, tags) = (editing.id, editing.priority, parse_tags(&editing.tags));
+        self.editing = None;
+        if self.tasks.iter().any(|task| task.id == id && (task.title != title || task.priority != priority || task.due != due || task.recurrence != recurrence || task.tags != tags)) {
+            self.record();
+            if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
+                task.title = title;
+                task.priority = priority;
+                task.due = due;
+                task.recurrence = recurrence;
+                task.tags = tags;
+            }
+        }
+    }
+
+    pub fn edit_view(&self, editing: &Editing) -> Element<'_, Message> {
+        let titled = !editing.title.trim().is_empty();
+        let dated = parse_due(&editing.due).is_some();
+        let repeats = parse_repeat(&editing.repeat).is_some();
+        Row::new()
+            .push(text_input("Task ...", &editing.title)
+                .id(input_id())
+                .on_input(Message::EditTitle)
+                .on_submit(Message::ConfirmEdit)
+                .width(Length::Fixed(240.0)))
+            .push(pick_list(Priority::ALL, Some(editing.priority), Message::EditPriority))
+            .push(text_input("Due dd/mm/yyyy hh:mm", &editing.due)
+                .on_input(Message::EditDue)
+                .on_submit(Message::ConfirmEdit)
+                .width(Length::Fixed(170.0)))
+            .push(text_input("Repeat e.g. weekly mon,thu", &editing.repeat)
+                .on_input(Message::EditRepeat)
+                .on_submit(Message::ConfirmEdit)
+                .width(Length::Fixed(200.0)))
+            .push(text_input("Tags, comma separated", &editing.tags)
+                .on_input(Message::EditTags)
+                .on_submit(Message::ConfirmEdit)
+                .width(Length::Fixed(180.0)))
+            .push(button("Confirm").on_press_maybe(if titled && dated && repeats {Some(Message::ConfirmEdit)} else {None}))
+            .push(button("Cancel").on_press(Message::Cancel))
+            .push_maybe(if titled {None} else {Some(text("A task needs some text").size(14).color(get_rgb_color(220, 8, 51)))})
+            .push_maybe(if dated {None} else {Some(text("Enter a due date as dd/mm/yyyy hh:mm").size(14).color(get_rgb_color(220, 8, 51)))})
+            .push_maybe(if repeats {None} else {Some(text(REPEAT_HINT).size(14).color(get_rgb_color(220, 8, 51)))})
+            .spacing(10).align_y(Alignment::Center)
+            .into()
+    }
+}
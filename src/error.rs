@@ -0,0 +1,56 @@
+use std::{fmt, io};
+
+// Everything that can go wrong while reading a save file back in
+#[derive(Debug, PartialEq, Clone)]
+pub enum LoadError {
+    Io(io::ErrorKind),
+    Parse { line: usize, column: usize, message: String },
+    MissingField(String),
+    WrongType { field: String, message: String },
+    UnsupportedVersion(u64),
+}
+
+impl LoadError {
+    // Syntax errors from reading the raw text
+    pub fn parse(error: serde_json::Error) -> Self {
+        // The position is kept separately, so drop serde_json's own suffix
+        let mut message = error.to_string();
+        if let Some(index) = message.rfind(" at line ") {
+            message.truncate(index);
+        }
+        LoadError::Parse {
+            line: error.line(),
+            column: error.column(),
+            message,
+        }
+    }
+
+    // Errors from mapping the parsed document onto the save types, with the path of the offending field
+    pub fn data(error: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        let path = error.path().to_string();
+        let message = error.inner().to_string();
+        if let Some(field) = message.strip_prefix("missing field `").and_then(|rest| rest.split('`').next()) {
+            LoadError::MissingField(if path == "." {field.to_owned()} else {format!("{}.{}", path, field)})
+        } else {
+            LoadError::WrongType { field: path, message }
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(kind) => write!(f, "Could not read file: {}", kind),
+            LoadError::Parse { line, column, message } => write!(f, "Invalid JSON at line {}, column {}: {}", line, column, message),
+            LoadError::MissingField(field) => write!(f, "Save file is missing the field `{}`", field),
+            LoadError::WrongType { field, message } => write!(f, "Field `{}` has the wrong type: {}", field, message),
+            LoadError::UnsupportedVersion(version) => write!(f, "Save file version {} is newer than this app supports", version),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(error: io::Error) -> Self {
+        LoadError::Io(error.kind())
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt;
+
+use iced::{Alignment, Element, Length};
+use iced::widget::{pick_list, text, text_input, Row};
+
+use crate::task::{Priority, Task};
+use crate::{Message, ToDo};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum SortMode {
+    // The order set by hand, the only one tasks can be dragged in
+    #[default]
+    Manual,
+    Created,
+    Priority,
+    DueDate,
+    TimeSpent,
+    Alphabetical,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 6] = [SortMode::Manual, SortMode::Created, SortMode::Priority, SortMode::DueDate, SortMode::TimeSpent, SortMode::Alphabetical];
+}
+
+impl fmt::Display for SortMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            SortMode::Manual => "Manual",
+            SortMode::Created => "Created",
+            SortMode::Priority => "Priority",
+            SortMode::DueDate => "Due Date",
+            SortMode::TimeSpent => "Time Spent",
+            SortMode::Alphabetical => "Alphabetical",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Current,
+    Started,
+    NotStarted,
+}
+
+impl StatusFilter {
+    pub const ALL: [StatusFilter; 4] = [StatusFilter::All, StatusFilter::Current, StatusFilter::Started, StatusFilter::NotStarted];
+}
+
+impl fmt::Display for StatusFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            StatusFilter::All => "All Tasks",
+            StatusFilter::Current => "Current Task",
+            StatusFilter::Started => "Started",
+            StatusFilter::NotStarted => "Not Started",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub enum TagFilter {
+    #[default]
+    All,
+    Tag(String),
+}
+
+impl fmt::Display for TagFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagFilter::All => write!(f, "All Tags"),
+            TagFilter::Tag(tag) => write!(f, "#{}", tag),
+        }
+    }
+}
+
+// How the task list is shown, only ever changes the view and never the order of ToDo::tasks
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct TaskFilter {
+    pub sort: SortMode,
+    pub status: StatusFilter,
+    pub tag: TagFilter,
+    pub search: String,
+}
+
+impl TaskFilter {
+    fn matches(&self, task: &Task, active: Option<u64>) -> bool {
+        let status = match self.status {
+            StatusFilter::All => true,
+            StatusFilter::Current => active == Some(task.id),
+            StatusFilter::Started => !task.time.export_time().is_zero(),
+            StatusFilter::NotStarted => task.time.export_time().is_zero(),
+        };
+        let tagged = match &self.tag {
+            TagFilter::All => true,
+            TagFilter::Tag(tag) => task.tags.iter().any(|own| own.eq_ignore_ascii_case(tag)),
+        };
+        let search = self.search.trim().to_lowercase();
+        status && tagged && (search.is_empty() || task.title.to_lowercase().contains(&search) || task.notes.to_lowercase().contains(&search)
+            || task.subtasks.iter().any(|subtask| subtask.title.to_lowercase().contains(&search)))
+    }
+}
+
+impl ToDo {
+    // Tasks to show with their index in ToDo::tasks, leaving out the one being added
+    pub fn visible_tasks(&self) -> Vec<(usize, &Task)> {
+        let adding = if self.add {self.tasks.len().checked_sub(1)} else {None};
+        let mut tasks: Vec<(usize, &Task)> = self.tasks.iter().enumerate()
+            .filter(|(index, task)| Some(*index) != adding && self.filter.matches(task, self.active))
+            .collect();
+        match self.filter.sort {
+            SortMode::Manual => {},
+            SortMode::Created => tasks.sort_by_key(|(_, task)| task.created),
+            SortMode::Priority => tasks.sort_by_key(|(_, task)| std::cmp::Reverse(task.priority)),
+            // Soonest first, tasks without a due date go last
+            SortMode::DueDate => tasks.sort_by_key(|(_, task)| (task.due.is_none(), task.due.map(|due| due.moment()))),
+            SortMode::TimeSpent => tasks.sort_by_key(|(_, task)| std::cmp::Reverse(task.time.export_time())),
+            SortMode::Alphabetical => tasks.sort_by_key(|(_, task)| task.title.to_lowercase()),
+        }
+        // Stable, so pinned tasks keep the chosen order among themselves
+        if self.config.pin_high_priority {
+            tasks.sort_by_key(|(_, task)| task.priority < Priority::High);
+        }
+        tasks
+    }
+
+    // Every tag used in the shown list
+    fn tag_options(&self) -> Vec<TagFilter> {
+        let mut tags: Vec<&String> = self.tasks.iter().flat_map(|task| task.tags.iter()).collect();
+        tags.sort_by_key(|tag| tag.to_lowercase());
+        tags.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        std::iter::once(TagFilter::All).chain(tags.into_iter().map(|tag| TagFilter::Tag(tag.clone()))).collect()
+    }
+
+    pub fn filter_view(&self) -> Element<'_, Message> {
+        Row::new()
+            .push(text("Sort"))
+            .push(pick_list(SortMode::ALL, Some(self.filter.sort), Message::Sort))
+            .push(text("Show"))
+            .push(pick_list(StatusFilter::ALL, Some(self.filter.status), Message::StatusFilter))
+            .push(pick_list(self.tag_options(), Some(self.filter.tag.clone()), Message::TagFilter))
+            .push(text_input("Search ...", &self.filter.search)
+                .on_input(Message::Search)
+                .width(Length::Fixed(200.0)))
+            .spacing(10).align_y(Alignment::Center)
+            .into()
+    }
+}
@@ -1,67 +1,208 @@
-use json::{self, JsonValue};
-use std::{io, path::PathBuf, time::Duration};
+use chrono::prelude::Local;
+use std::{io, path::{Path, PathBuf}, time::SystemTime};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::io::AsyncWriteExt;
 
 use crate::ToDo;
+use crate::error::LoadError;
+use crate::save::SaveFile;
 
-fn save_to_json(contents: &crate::ToDo) -> JsonValue{
-    let cur_task;
-    let rest;
-    cur_task = (contents.current_task.export_time().as_secs(), (contents.current_task.export_time().as_millis()%1000) as u32);
-
-    rest = (contents.break_time.export_time().as_secs(), (contents.break_time.export_time().as_millis()%1000) as u32);
-    let save = json::object!{
-        completed: contents.complete,
-        removed: contents.removed,
-        tasks: contents.tasks.clone(),
-        break_time: [
-            rest.0,
-            rest.1,
-        ],
-        cur_task: [
-            cur_task.0,
-            cur_task.1,
-        ],
-        prev_task: [
-            contents.last_task.export_time().as_secs(),
-            (contents.last_task.export_time().as_millis() % 1000) as u32
-        ]
-    };
-    println!("{}", json::stringify_pretty(save.clone(), 4));
-    save
+// Contents of a directory as shown in the file browser
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Listing {
+    pub dirs: Vec<PathBuf>,
+    pub files: Vec<PathBuf>,
+}
+
+// A copy of an overwritten save, and the save it was taken from when that is known
+#[derive(Debug, PartialEq, Clone)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub source: Option<PathBuf>,
+}
+
+// Names the save a folder of backups belongs to
+const SOURCE_FILE: &str = "source.txt";
+
+// Where copies of overwritten saves go, and how many of each are kept
+#[derive(Debug, PartialEq, Clone)]
+pub struct Backups {
+    pub dir: PathBuf,
+    pub keep: usize,
+}
+
+pub async fn save_to_file(path: PathBuf, content: SaveFile, backups: Backups) -> Result<PathBuf, io::ErrorKind>{
+    if let Some(dir) = path.parent() && tokio::fs::metadata(dir).await.is_err() {
+        let _ = tokio::fs::create_dir_all(dir).await.map_err(|error| eprintln!("Failed to create directory {}", error.kind()));
+    }
+    let contents = serde_json::to_string_pretty(&content).map_err(|_| io::ErrorKind::InvalidData)?;
+    // A failed backup shouldn't stop the save itself
+    if let Err(err) = backup_file(&path, &backups).await {
+        eprintln!("Backup failed {}", err);
+    }
+    write_atomic(&path, contents.as_bytes()).await.map_err(|error| error.kind())?;
+    Ok(path)
+}
+
+// Numbers the temporary files, so writes to the same target never share one
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// Writes to a temporary file next to the target, syncs it, then renames it over the target,
+// so a crash part way through leaves either the old or the new file but never half of one
+pub async fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut temp_name = path.file_name().ok_or(io::ErrorKind::InvalidInput)?.to_owned();
+    temp_name.push(format!(".{}-{}.tmp", std::process::id(), TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)));
+    let temp = path.with_file_name(temp_name);
+
+    let mut file = tokio::fs::File::create(&temp).await?;
+    file.write_all(contents).await?;
+    file.sync_all().await?;
+    drop(file);
+    if let Err(err) = tokio::fs::rename(&temp, path).await {
+        let _ = tokio::fs::remove_file(&temp).await;
+        return Err(err);
+    }
+
+    // Syncing the directory makes the rename itself durable, not every platform allows opening one
+    if let Some(dir) = path.parent() && let Ok(dir) = tokio::fs::File::open(dir).await {
+        let _ = dir.sync_all().await;
+    }
+    Ok(())
 }
 
-pub async fn save_to_file(path: PathBuf, filename: String, content: crate::ToDo) -> Result<(), io::ErrorKind>{
-    // println!("Recieved Save");
-    if tokio::fs::metadata(&path).await.is_err() {
-        let _ = tokio::fs::create_dir(&path).await.map_err(|error| eprintln!("Failed to create directory {}", error.kind()));
+// Each save gets its own folder of backups, named after the file and a hash of its full path,
+// so saves with the same name in different directories don't share a rotation
+fn backup_folder(path: &Path, stem: &str, backups: &Backups) -> PathBuf {
+    // FNV-1a, unlike the std hasher it stays the same between builds
+    let hash = path.as_os_str().as_encoded_bytes().iter()
+        .fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3));
+    backups.dir.join(format!("{}-{:016x}", stem, hash))
+}
+
+// Copies the file about to be overwritten into its backup folder as name-YYYYMMDD-HHMMSS.json,
+// later backups in the same second get a -2, -3 ... counter
+async fn backup_file(path: &Path, backups: &Backups) -> io::Result<()> {
+    if backups.keep == 0 || tokio::fs::metadata(path).await.is_err() {
+        return Ok(());
+    }
+    let path = tokio::fs::canonicalize(path).await?;
+    let stem = path.file_stem().ok_or(io::ErrorKind::InvalidInput)?.to_string_lossy().into_owned();
+    let folder = backup_folder(&path, &stem, backups);
+    tokio::fs::create_dir_all(&folder).await?;
+    let source = folder.join(SOURCE_FILE);
+    if tokio::fs::metadata(&source).await.is_err() {
+        write_atomic(&source, path.as_os_str().as_encoded_bytes()).await?;
     }
-    let full_path = if let Some(dir) = path.to_str() {dir.to_owned() + &filename} else {return Err(io::ErrorKind::InvalidInput);};
-    tokio::fs::write(full_path, json::stringify_pretty(save_to_json(&content), 4)).await.map_err(|error| error.kind())
+    let contents = tokio::fs::read(&path).await?;
+    let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut target = folder.join(format!("{}-{}.json", stem, stamp));
+    let mut count = 1;
+    // Creating the file claims the name before it is written
+    loop {
+        match tokio::fs::OpenOptions::new().write(true).create_new(true).open(&target).await {
+            Ok(_) => break,
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                count += 1;
+                target = folder.join(format!("{}-{}-{}.json", stem, stamp, count));
+            },
+            Err(err) => return Err(err),
+        }
+    }
+    write_atomic(&target, &contents).await?;
+    prune_backups(&stem, &folder, backups.keep).await
 }
 
-pub async fn read_from_file(path: PathBuf, filename: String) -> Result<ToDo, io::ErrorKind> {
-    let full_path = if let Some(dir) = path.to_str() {dir.to_owned() + &filename} else {return Err(io::ErrorKind::InvalidInput);};
-    let values = tokio::fs::read_to_string(full_path).await.map_err(|error| error.kind())?;
-    Ok(get_todo(&mut parse_json(values)))
+// Deletes the oldest backups of a file beyond the number to keep
+async fn prune_backups(stem: &str, folder: &Path, keep: usize) -> io::Result<()> {
+    let prefix = format!("{}-", stem);
+    let mut matching: Vec<PathBuf> = json_files(folder).await?.into_iter().map(|(_, path)| path)
+        .filter(|backup| backup.file_name().is_some_and(|name| name.to_string_lossy().strip_prefix(&prefix).is_some_and(is_backup_suffix)))
+        .collect();
+    for old in matching.drain(keep.min(matching.len())..) {
+        tokio::fs::remove_file(old).await?;
+    }
+    Ok(())
 }
 
-fn parse_json(read_in: String) -> JsonValue {
-    let parsed = json::parse(&read_in);
-    match (parsed){
-        Ok(values) => values,
-        Err(_) => {panic!("Failed to parse Json")},
+// Matches the YYYYMMDD-HHMMSS.json or YYYYMMDD-HHMMSS-N.json part of a backup name, so "save" doesn't prune "save-2" backups
+pub fn is_backup_suffix(suffix: &str) -> bool {
+    suffix.strip_suffix(".json").is_some_and(|stamp| {
+        let (stamp, count) = match stamp.get(15..) {
+            Some(rest) if !rest.is_empty() => (&stamp[..15], rest.strip_prefix('-')),
+            _ => (stamp, Some("1")),
+        };
+        stamp.len() == 15 && stamp.char_indices().all(|(index, c)| if index == 8 {c == '-'} else {c.is_ascii_digit()})
+            && count.is_some_and(|count| !count.is_empty() && count.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+// The .json files directly in a directory with when they were written, newest first
+async fn json_files(dir: &Path) -> io::Result<Vec<(SystemTime, PathBuf)>> {
+    let mut files: Vec<(SystemTime, PathBuf)> = Vec::new();
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if is_json(&path) && let Ok(modified) = entry.metadata().await.and_then(|metadata| metadata.modified()) {
+            files.push((modified, path));
+        }
     }
-        
+    files.sort_by(|a, b| b.cmp(a));
+    Ok(files)
 }
 
-pub fn get_todo(json: &mut JsonValue) -> ToDo {
-    let completed = json["completed"].as_u32().unwrap_or(0);
-    let removed = json["removed"].as_u32().unwrap_or(0);
-    let tasks: Vec<String> = json["tasks"].members().filter_map(|v| v.as_str().map(String::from)).collect();
-    let break_time:Vec<u64> = json["break_time"].members().filter_map(|x| x.as_u64()).collect();
-    let cur_task: Vec<u64> = json["cur_task"].members().filter_map(|x| x.as_u64()).collect();
-    let prev_task: Vec<u64> = json["prev_task"].members().filter_map(|x| x.as_u64()).collect();
-    ToDo::from(tasks, completed, removed, Duration::new(cur_task[0], cur_task[1] as u32), Duration::new(prev_task[0], prev_task[1] as u32), Duration::new(break_time[0], break_time[1] as u32))
+// All backups, from every save's folder and any older ones loose in the directory, newest first
+pub async fn list_backups(dir: PathBuf) -> io::Result<Vec<Backup>> {
+    let mut backups: Vec<(SystemTime, Backup)> = json_files(&dir).await?.into_iter()
+        .map(|(modified, path)| (modified, Backup { path, source: None }))
+        .collect();
+    if let Ok(mut entries) = tokio::fs::read_dir(&dir).await {
+        while let Some(entry) = entries.next_entry().await? {
+            let folder = entry.path();
+            if !entry.file_type().await.is_ok_and(|file_type| file_type.is_dir()) {
+                continue;
+            }
+            let source = tokio::fs::read_to_string(folder.join(SOURCE_FILE)).await.ok().map(PathBuf::from);
+            backups.extend(json_files(&folder).await?.into_iter().map(|(modified, path)| (modified, Backup { path, source: source.clone() })));
+        }
+    }
+    backups.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.path.cmp(&a.1.path)));
+    Ok(backups.into_iter().map(|(_, backup)| backup).collect())
+}
+
+pub async fn read_from_file(path: PathBuf) -> Result<(PathBuf, Box<ToDo>), LoadError> {
+    let values = tokio::fs::read_to_string(&path).await?;
+    let todo = get_todo(&values)?;
+    Ok((path, Box::new(todo)))
+}
+
+pub fn get_todo(contents: &str) -> Result<ToDo, LoadError> {
+    let value = serde_json::from_str(contents).map_err(LoadError::parse)?;
+    let save = SaveFile::from_value(value)?;
+    Ok(save.into_todo())
+}
 
+// Lists sub directories and .json files, both sorted by name
+pub async fn list_directory(path: PathBuf) -> Result<Listing, io::ErrorKind> {
+    let mut listing = Listing::default();
+    let mut entries = tokio::fs::read_dir(&path).await.map_err(|error| error.kind())?;
+    while let Some(entry) = entries.next_entry().await.map_err(|error| error.kind())? {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type().await else {continue};
+        if file_type.is_dir() {
+            listing.dirs.push(path);
+        } else if is_json(&path) {
+            listing.files.push(path);
+        }
+    }
+    listing.dirs.sort();
+    listing.files.sort();
+    Ok(listing)
+}
 
-}
\ No newline at end of file
+pub fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+}
@@ -0,0 +1,146 @@
+use crate::lists::TaskList;
+use crate::task::Task;
+use crate::time::{DayLog, Time};
+use crate::ToDo;
+
+// Upper bound on how many steps can be undone
+const HISTORY_LIMIT: usize = 100;
+
+// How many ticks the undo notice stays on screen
+const NOTICE_TICKS: u32 = 6;
+
+// Everything a state-changing message can touch
+#[derive(Debug, PartialEq, Clone)]
+pub struct Snapshot {
+    tasks: Vec<Task>,
+    lists: Vec<TaskList>,
+    current_list: usize,
+    archive: Vec<Task>,
+    next_id: u64,
+    complete: u32,
+    removed: u32,
+    active: Option<u64>,
+    last_task: Time,
+    rest: bool,
+    break_time: Time,
+    break_log: DayLog,
+    sleep: bool,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct History {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl History {
+    pub fn push(&mut self, snapshot: Snapshot) {
+        if self.undo.len() == HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(snapshot);
+        self.redo.clear();
+    }
+
+    // Swaps the current state for the last recorded one
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+// Short lived message with an undo button, shown after destructive actions
+#[derive(Debug, PartialEq, Clone)]
+pub struct Notice {
+    pub text: String,
+    pub ticks: u32,
+}
+
+impl Notice {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ticks: NOTICE_TICKS,
+        }
+    }
+}
+
+impl ToDo {
+    pub fn snapshot(&self) -> Snapshot {
+        // A task still being added isn't part of any state to go back to
+        Snapshot {
+            tasks: self.settled_tasks().to_vec(),
+            lists: self.lists.clone(),
+            current_list: self.current_list,
+            archive: self.archive.clone(),
+            next_id: self.next_id,
+            complete: self.complete,
+            removed: self.removed,
+            active: self.active,
+            last_task: self.last_task.clone(),
+            rest: self.rest,
+            break_time: self.break_time.clone(),
+            break_log: self.break_log.clone(),
+            sleep: self.sleep,
+        }
+    }
+
+    // Records the current state so the next change can be undone
+    pub fn record(&mut self) {
+        let snapshot = self.snapshot();
+        self.history.push(snapshot);
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.tasks = snapshot.tasks;
+        self.lists = snapshot.lists;
+        self.current_list = snapshot.current_list;
+        self.archive = snapshot.archive;
+        self.next_id = snapshot.next_id;
+        self.complete = snapshot.complete;
+        self.removed = snapshot.removed;
+        self.active = snapshot.active;
+        self.last_task = snapshot.last_task;
+        self.rest = snapshot.rest;
+        self.break_time = snapshot.break_time;
+        self.break_log = snapshot.break_log;
+        self.sleep = snapshot.sleep;
+        // Any half finished input belongs to the state being left behind
+        self.add = false;
+        self.reset = false;
+        self.notice = None;
+    }
+
+    pub fn undo(&mut self) {
+        if self.add {
+            // Undoing mid-add just cancels the new task
+            self.cancel_new();
+        } else if let Some(previous) = self.history.undo(self.snapshot()) {
+            self.restore(previous);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if self.add {
+            return;
+        }
+        if let Some(next) = self.history.redo(self.snapshot()) {
+            self.restore(next);
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+use iced::{event, mouse, Alignment, Element, Event};
+use iced::widget::{button, text, Row};
+
+use crate::time::format_duration;
+use crate::{Message, ToDo};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IdleChoice {
+    Keep,
+    Discard,
+    Break,
+}
+
+// A stretch without any window events while a task was being timed
+#[derive(Debug, PartialEq, Clone)]
+pub struct Idle {
+    pub task: u64,
+    pub since: Instant,
+    // When the task's timer was last started, later than since if it was paused after the last input
+    pub running_from: Instant,
+    // Set once the user is back, the task timer is paused until then
+    pub returned: Option<Duration>,
+    // Part of the idle stretch the task was running for, what Keep and Break hand out
+    pub task_share: Duration,
+}
+
+// Window events that count as someone being at the keyboard
+pub fn activity(event: Event, _status: event::Status, _window: iced::window::Id) -> Option<Message> {
+    match event {
+        Event::Keyboard(_) | Event::Mouse(mouse::Event::CursorMoved { .. } | mouse::Event::ButtonPressed(_) | mouse::Event::WheelScrolled { .. }) => Some(Message::Activity),
+        _ => None,
+    }
+}
+
+impl ToDo {
+    pub fn is_idle(&self) -> bool {
+        self.idle.as_ref().is_some_and(|idle| idle.returned.is_none())
+    }
+
+    // Pauses the active task once nothing has happened for the configured number of minutes
+    pub fn check_idle(&mut self) {
+        let limit = Duration::from_secs(u64::from(self.config.idle_minutes) * 60);
+        if limit.is_zero() || self.idle.is_some() || !self.task_running() || self.last_activity.elapsed() < limit {
+            return;
+        }
+        let since = self.last_activity;
+        let Some(task) = self.active_task_mut() else {return};
+        // The idle minutes were already counted, take them back off until the user decides,
+        // only the ones the task ran for, time before a pause ended isn't the task's
+        let running_from = task.time.stretch_start().max(since);
+        let counted = task.time.current_since(since);
+        task.time.swap_current();
+        task.time.subtract(counted);
+        let task = task.id;
+        self.idle = Some(Idle { task, since, running_from, returned: None, task_share: Duration::ZERO });
+    }
+
+    // Any window event, resumes the task timer if it was paused for being idle
+    pub fn mark_activity(&mut self) {
+        self.last_activity = Instant::now();
+        if let Some(idle) = &mut self.idle && idle.returned.is_none() {
+            idle.returned = Some(idle.since.elapsed());
+            idle.task_share = idle.running_from.elapsed();
+            if self.task_running() && let Some(task) = self.active_task_mut() {
+                task.time.new_start();
+            }
+        }
+    }
+
+    pub fn resolve_idle(&mut self, choice: IdleChoice) {
+        let Some(Idle { task, returned: Some(_), task_share, .. }) = self.idle.take() else {return};
+        match choice {
+            IdleChoice::Keep => {
+                if let Some(task) = self.all_tasks_mut().find(|entry| entry.id == task) {
+                    task.time.add(task_share);
+                }
+            },
+            IdleChoice::Discard => {},
+            IdleChoice::Break => self.break_time.add(task_share),
+        }
+    }
+
+    pub fn idle_view(&self) -> Option<Element<'_, Message>> {
+        let duration = self.idle.as_ref()?.returned?;
+        Some(
+            Row::new()
+                .push(text(format!("You were idle for {}, keep it, discard it or move it to break time?", format_duration(duration))).size(16))
+                .push(button("Keep").on_press(Message::ResolveIdle(IdleChoice::Keep)))
+                .push(button("Discard").on_press(Message::ResolveIdle(IdleChoice::Discard)))
+                .push(button("Break").on_press(Message::ResolveIdle(IdleChoice::Break)))
+                .spacing(10).align_y(Alignment::Center)
+                .into()
+        )
+    }
+}
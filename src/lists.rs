@@ -0,0 +1,131 @@
+use iced::{Alignment, Color, Element, Length, Theme};
+use iced::widget::{button, horizontal_space, text, text_input, Row};
+
+use crate::filter::TagFilter;
+use crate::styles::buttons::*;
+use crate::styles::*;
+use crate::task::Task;
+use crate::{Message, ToDo};
+
+pub const DEFAULT_LIST: &str = "Tasks";
+
+// A named list with its own tasks and counters.
+// The list being shown lives in ToDo::tasks, ToDo::complete and ToDo::removed, its entry here only keeps the name
+#[derive(Debug, PartialEq, Clone)]
+pub struct TaskList {
+    pub name: String,
+    pub tasks: Vec<Task>,
+    pub complete: u32,
+    pub removed: u32,
+}
+
+impl TaskList {
+    pub fn new(name: String) -> Self {
+        Self { name, tasks: Vec::new(), complete: 0, removed: 0 }
+    }
+}
+
+// Tags typed as a comma separated list, without blanks or repeats
+pub fn parse_tags(value: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in value.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|existing| existing.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_owned());
+        }
+    }
+    tags
+}
+
+impl ToDo {
+    // Tasks of every list, the one shown first
+    pub fn all_tasks(&self) -> impl Iterator<Item = &Task> {
+        self.tasks.iter().chain(self.lists.iter().flat_map(|list| list.tasks.iter()))
+    }
+
+    // Tasks of the shown list, without one still being added
+    pub fn settled_tasks(&self) -> &[Task] {
+        if self.add {&self.tasks[..self.tasks.len().saturating_sub(1)]} else {&self.tasks}
+    }
+
+    pub fn all_tasks_mut(&mut self) -> impl Iterator<Item = &mut Task> {
+        self.tasks.iter_mut().chain(self.lists.iter_mut().flat_map(|list| list.tasks.iter_mut()))
+    }
+
+    // Stores the shown list back into its entry and brings another one out, the active task keeps timing either way
+    pub fn switch_list(&mut self, index: usize) {
+        if index == self.current_list || index >= self.lists.len() || self.add {
+            return;
+        }
+        let current = &mut self.lists[self.current_list];
+        current.tasks = std::mem::take(&mut self.tasks);
+        current.complete = self.complete;
+        current.removed = self.removed;
+        self.load_list(index);
+    }
+
+    fn load_list(&mut self, index: usize) {
+        let list = &mut self.lists[index];
+        self.tasks = std::mem::take(&mut list.tasks);
+        self.complete = list.complete;
+        self.removed = list.removed;
+        self.current_list = index;
+        // Row state belongs to the list being left
+        self.editing = None;
+        self.expanded = None;
+        self.notes_editor = None;
+        self.drag = None;
+        self.focused = None;
+        self.filter.tag = TagFilter::All;
+    }
+
+    pub fn add_list(&mut self) {
+        let name = self.new_list.trim().to_owned();
+        if name.is_empty() || self.lists.iter().any(|list| list.name == name) || self.add {
+            return;
+        }
+        self.record();
+        self.lists.push(TaskList::new(name));
+        self.new_list = String::new();
+        self.switch_list(self.lists.len() - 1);
+    }
+
+    // Only empty lists can go, so no tasks are lost with them
+    pub fn remove_list(&mut self) {
+        if self.lists.len() < 2 || !self.tasks.is_empty() || self.add {
+            return;
+        }
+        self.record();
+        self.lists.remove(self.current_list);
+        self.load_list(self.current_list.min(self.lists.len() - 1));
+    }
+
+    pub fn lists_view(&self) -> Element<'_, Message> {
+        let radius = 2;
+        let mut tabs: Row<'_, Message> = Row::new().spacing(10).align_y(Alignment::Center);
+        for (index, list) in self.lists.iter().enumerate() {
+            let selected = index == self.current_list;
+            let open = if selected {self.tasks.len() - usize::from(self.add)} else {list.tasks.len()};
+            tabs = tabs.push(
+                button(text(format!("{} ({})", list.name, open)))
+                    .on_press_maybe(if selected || self.add {None} else {Some(Message::SwitchList(index))})
+                    .style(move |_: &Theme, _| {
+                        if selected {
+                            style_button(get_rgb_color(0, 155, 0), Color::WHITE, radius)
+                        } else {
+                            style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
+                        }
+                    })
+            );
+        }
+        let name = self.new_list.trim();
+        let valid = !name.is_empty() && !self.lists.iter().any(|list| list.name == name);
+        tabs.push(horizontal_space())
+            .push(text_input("New List ...", &self.new_list)
+                .on_input(Message::NewListName)
+                .on_submit(Message::AddList)
+                .width(Length::Fixed(160.0)))
+            .push(button("Add List").on_press_maybe(if valid && !self.add {Some(Message::AddList)} else {None}))
+            .push(button("Remove List").on_press_maybe(if self.lists.len() > 1 && self.tasks.is_empty() && !self.add {Some(Message::RemoveList)} else {None}))
+            .into()
+    }
+}
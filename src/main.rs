@@ -1,51 +1,116 @@
 use std::io;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
-use fs::{save_to_file, read_from_file};
-use iced::{Alignment, Color, Element, Length, Subscription, Task, Theme};
-use iced::widget::{button, horizontal_space, text, text_input, vertical_space, Column, Container, Row, Scrollable};
+use fs::save_to_file;
+use iced::{keyboard, mouse, Alignment, Border, Color, Element, Length, Subscription, Task, Theme};
+use iced::widget::{button, container, horizontal_space, markdown, mouse_area, pick_list, text, text_editor, text_input, vertical_space, Column, Container, Row, Scrollable};
 use chrono::prelude::{DateTime, Local};
 
+mod alerts;
+mod archive;
+mod browser;
+mod cli;
+mod config;
+mod edit;
+mod error;
+mod filter;
 mod styles;
+mod subtask;
 mod fs;
+mod history;
+mod idle;
+mod lists;
+mod notes;
+mod pomodoro;
+mod recur;
+mod reorder;
+mod report;
+mod resume;
+mod save;
+mod settings;
+mod stats;
+mod task;
 mod time;
 
-// #[cfg(test)]
-// mod tests;
+#[cfg(test)]
+mod tests;
 
 use styles::buttons::*;
 use styles::*;
+use browser::{Browser, Mode};
+use config::{save_config, Config};
+use history::{History, Notice};
 use time::Time;
 
 // DONE: Update Timing System, to improve consistency
 // DONE: Migrate to new Time struct to reduce complexity
 // DONE: Implement auto-save functionality, on certain tick count
 // TODO: Change Styling for file opening
-// TODO: Implement file system, saves, auto-load on start
+// DONE: Implement file system, saves, auto-load on start
 // TODO: Move auto save to separate struct
-// TODO: Create Settings Menu, autosave on task completion
-// TODO: Move to Serde JSON?
-// TODO: Feature Request: Allow for dragging + reordering Tasks
-// TODO: Allow sorting tasks, edit on click
-// TODO: Create Tests?
+// DONE: Create Settings Menu, autosave on task completion
+// DONE: Move to Serde JSON
+// DONE: Feature Request: Allow for dragging + reordering Tasks
+// DONE: Allow sorting tasks
+// DONE: Edit on click
+// DONE: Create Tests
 
 
 #[derive(Debug, PartialEq, Clone)]
 struct ToDo{
     time: DateTime<Local>,
     clock: String,
-    tasks: Vec<String>,
+    tasks: Vec<task::Task>,
+    lists: Vec<lists::TaskList>,
+    current_list: usize,
+    new_list: String,
+    archive: Vec<task::Task>,
+    archive_filter: String,
+    screen: Screen,
+    next_id: u64,
     add: bool,
     complete: u32,
     removed: u32,
-    current_task: Time,
+    active: Option<u64>,
     last_task:Time,
     rest:bool,
     break_time: Time,
+    break_log: time::DayLog,
     sleep:bool,
     reset:bool,
-    tick_count: i32,
-    auto_save: bool,
+    tick_count: u32,
+    history: History,
+    notice: Option<Notice>,
+    error: Option<String>,
+    current_file: Option<PathBuf>,
+    browser: Browser,
+    data_dir: PathBuf,
+    config: Config,
+    draft: settings::Draft,
+    gap: Option<resume::Gap>,
+    pomodoro: Option<pomodoro::Pomodoro>,
+    alerts: Vec<String>,
+    fired: alerts::Fired,
+    last_activity: Instant,
+    idle: Option<idle::Idle>,
+    report: report::ReportState,
+    stats_range: u32,
+    editing: Option<edit::Editing>,
+    drag: Option<reorder::Drag>,
+    // Row Alt+Up and Alt+Down move
+    focused: Option<u64>,
+    filter: filter::TaskFilter,
+    // Due date typed for the task being added
+    new_due: String,
+    new_repeat: String,
+    new_tags: String,
+    // Task whose checklist is open and the subtask being typed for it
+    expanded: Option<u64>,
+    new_subtask: String,
+    // Notes being edited and the Markdown shown for the open task
+    notes_editor: Option<notes::NotesEditor>,
+    rendered_notes: Option<notes::RenderedNotes>,
 }
 
 impl Default for ToDo {
@@ -54,21 +119,65 @@ impl Default for ToDo {
             time: Local::now(),
             clock: String::new(),
             tasks: Vec::new(),
+            lists: vec![lists::TaskList::new(lists::DEFAULT_LIST.to_owned())],
+            current_list: 0,
+            new_list: String::new(),
+            archive: Vec::new(),
+            archive_filter: String::new(),
+            screen: Screen::Tasks,
+            next_id: 0,
             add: false,
             complete: 0,
             removed: 0,
-            current_task: Time::new(Instant::now()),
+            active: None,
             last_task: Time::new(Instant::now()),
             rest: false,
             break_time: Time::new(Instant::now()),
+            break_log: time::DayLog::default(),
             sleep: false,
             reset: false,
             tick_count: 0,
-            auto_save: false,
+            history: History::default(),
+            notice: None,
+            error: None,
+            current_file: None,
+            browser: Browser::new(&PathBuf::new()),
+            data_dir: PathBuf::new(),
+            config: Config::default(),
+            draft: settings::Draft::default(),
+            gap: None,
+            pomodoro: None,
+            alerts: Vec::new(),
+            fired: alerts::Fired::default(),
+            last_activity: Instant::now(),
+            idle: None,
+            report: report::ReportState::default(),
+            stats_range: stats::RANGES[0],
+            editing: None,
+            drag: None,
+            focused: None,
+            filter: filter::TaskFilter::default(),
+            new_due: String::new(),
+            new_repeat: String::new(),
+            new_tags: String::new(),
+            expanded: None,
+            new_subtask: String::new(),
+            notes_editor: None,
+            rendered_notes: None,
         }
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Screen {
+    Tasks,
+    Archive,
+    Files,
+    Settings,
+    Report,
+    Stats,
+}
+
 #[derive(Debug, Clone)]
 enum Message{
     Reset(bool),
@@ -76,23 +185,122 @@ enum Message{
     Cancel,
     End,
     AddTask(String, i32),
+    Edit(u64),
+    EditTitle(String),
+    ConfirmEdit,
+    EditPriority(task::Priority),
+    NewPriority(task::Priority),
+    EditDue(String),
+    NewDue(String),
+    EditRepeat(String),
+    NewRepeat(String),
+    EditTags(String),
+    NewTags(String),
+    TagFilter(filter::TagFilter),
+    SwitchList(usize),
+    NewListName(String),
+    AddList,
+    RemoveList,
+    Expand(u64),
+    SubtaskText(String),
+    AddSubtask,
+    ToggleSubtask(u64, usize),
+    RemoveSubtask(u64, usize),
+    EditNotes(u64),
+    NotesAction(text_editor::Action),
+    SaveNotes,
+    LinkClicked(markdown::Url),
+    Drag(u64),
+    DragOver(usize),
+    Drop,
+    MoveUp,
+    MoveDown,
+    Focus(u64),
+    FocusUp,
+    FocusDown,
+    Sort(filter::SortMode),
+    StatusFilter(filter::StatusFilter),
+    Search(String),
     RemoveTask(i32, bool),
+    Select(u64),
+    Restore(u64),
+    Show(Screen),
+    ArchiveFilter(String),
+    Undo,
+    Redo,
     Tick,
     Break,
+    Pomodoro,
     Sleep,
     Save,
+    SaveAs,
     Open,
-    FileSave(Result<(), io::ErrorKind>),
-    FileOpen(Result<ToDo, io::ErrorKind>),
+    BrowseDirectory(String),
+    BrowseTo(PathBuf),
+    Refresh,
+    Listed(Result<fs::Listing, io::ErrorKind>),
+    BackupsListed(Result<Vec<fs::Backup>, io::ErrorKind>),
+    FileName(String),
+    PickFile(PathBuf),
+    ConfirmSaveAs,
+    AutoSave(Result<PathBuf, io::ErrorKind>),
+    FileSave(Result<PathBuf, io::ErrorKind>),
+    FileOpen(Result<(PathBuf, Box<ToDo>), error::LoadError>),
+    ConfigSaved(Result<(), io::ErrorKind>),
+    ResolveGap(bool),
+    OpenSettings,
+    Setting(settings::Change),
+    DismissError,
+    DismissAlert(usize),
+    Notified(Result<(), String>),
+    Activity,
+    ResolveIdle(idle::IdleChoice),
+    ReportPeriod(report::Period),
+    ReportDate(String),
+    ExportReport(report::Format),
+    ReportExported(Result<PathBuf, io::ErrorKind>),
+    StatsRange(u32),
 }
 
 impl ToDo {
-    pub fn from(task_list: Vec<String>, completed: u32, removed_tasks: u32, task_time: Duration, prev_task_time: Duration, break_time: Duration) -> Self{
+    pub fn new(data_dir: PathBuf, config: Config) -> Self {
+        ToDo {
+            browser: Browser::new(&data_dir.join("saves")),
+            data_dir,
+            config,
+            ..Default::default()
+        }
+    }
+
+    // Saves made from the app go here unless the user picks another directory
+    fn saves_dir(&self) -> PathBuf {
+        self.data_dir.join("saves")
+    }
+
+    fn backups_dir(&self) -> PathBuf {
+        self.data_dir.join("backups")
+    }
+
+    // Writes the current state to a file, keeping backups of whatever it replaces
+    fn save_to(&self, path: PathBuf, done: fn(Result<PathBuf, io::ErrorKind>) -> Message) -> Task<Message> {
+        let backups = fs::Backups { dir: self.backups_dir(), keep: self.config.backup_count as usize };
+        Task::perform(save_to_file(path, save::SaveFile::from(self), backups), done)
+    }
+
+    fn store_config(&self) -> Task<Message> {
+        Task::perform(save_config(self.data_dir.clone(), self.config.clone()), Message::ConfigSaved)
+    }
+
+    pub fn from(task_list: Vec<task::Task>, archive: Vec<task::Task>, active: Option<u64>, completed: u32, removed_tasks: u32, prev_task_time: Duration, break_time: Duration) -> Self{
+        // Ids must keep increasing past anything already loaded
+        let next_id = task_list.iter().chain(archive.iter()).map(|task| task.id + 1).max().unwrap_or(0);
         ToDo {
             tasks:task_list,
+            archive,
+            next_id,
             complete: completed,
             removed: removed_tasks,
-            current_task: Time::from(task_time),
+            active,
             last_task: Time::from(prev_task_time),
             break_time: Time::from(break_time),
             ..Default::default()
@@ -101,19 +309,51 @@ impl ToDo {
 
     fn replace(&mut self, todo: ToDo) {
         self.tasks = todo.tasks;
+        self.lists = todo.lists;
+        self.current_list = todo.current_list;
+        // A task still being added belonged to the replaced tasks
+        self.add = false;
+        self.fired = alerts::Fired::default();
+        self.editing = None;
+        self.expanded = None;
+        self.notes_editor = None;
+        self.drag = None;
+        self.focused = None;
+        self.filter.tag = filter::TagFilter::All;
+        self.archive = todo.archive;
+        self.next_id = todo.next_id;
         self.complete = todo.complete;
         self.removed = todo.removed;
-        self.current_task = todo.current_task;
+        self.active = todo.active;
         self.last_task = todo.last_task;
         self.break_time = todo.break_time;
+        self.break_log = todo.break_log;
+        self.rest = todo.rest;
+        self.sleep = todo.sleep;
+        self.gap = todo.gap;
+        // Short gaps are simply counted
+        if self.gap.as_ref().is_some_and(|gap| !gap.needs_prompt()) {
+            self.resolve_gap(true);
+        }
     }
 
-    pub fn view(&self) -> Element<Message>{
+    pub fn view(&self) -> Element<'_, Message>{
+        match self.screen {
+            Screen::Tasks => self.tasks_view(),
+            Screen::Archive => self.archive_view(),
+            Screen::Files => self.browser_view(),
+            Screen::Settings => self.settings_view(),
+            Screen::Report => self.report_view(),
+            Screen::Stats => self.stats_view(),
+        }
+    }
+
+    fn tasks_view(&self) -> Element<'_, Message>{
         // Sets the rounding radius for button elements with custom styles
         let radius = 2;
 
         // Stores all the contents on the screen
-        let mut main: Column<'_, Message> = Column::new().align_x(Alignment::Center).width(Length::Fill).padding(20).spacing(10).into();
+        let mut main: Column<'_, Message> = Column::new().align_x(Alignment::Center).width(Length::Fill).padding(20).spacing(10);
 
 
         main = main.push(
@@ -122,7 +362,7 @@ impl ToDo {
                 Row::new().push(if !self.rest && !self.reset {
                     button(
                         if !self.add {"New Task"} 
-                        else {"Confirm"}).on_press(Message::New)
+                        else {"Confirm"}).on_press_maybe(if self.add && !self.new_valid() {None} else {Some(Message::New)})
                     } 
                     else if self.reset {
                         button("Reset All").on_press(Message::Reset(false)).style(
@@ -140,15 +380,11 @@ impl ToDo {
                     }
                     else {
                         button(if !self.sleep {"Sleep"} else {"End Sleep"}).on_press(Message::Sleep).style(
-                            move |_: &Theme, status| {
-                                match status {
-                                    _ => {
-                                        if self.sleep{
-                                            style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
-                                        } else {
-                                            style_button(get_rgb_color(0, 155, 0), Color::WHITE, radius)
-                                        }
-                                    }
+                            move |_: &Theme, _| {
+                                if self.sleep{
+                                    style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
+                                } else {
+                                    style_button(get_rgb_color(0, 155, 0), Color::WHITE, radius)
                                 }
                         }
                     )
@@ -166,18 +402,15 @@ impl ToDo {
             .push_maybe(
                 if !self.add && !self.reset{
                     Some(button(
-                            if !self.rest {"Take a Break"} 
+                            if self.pomodoro.is_some() {"Skip Phase"}
+                            else if !self.rest {"Take a Break"} 
                             else {"End Break"}
                         ).on_press(Message::Break).style(
-                            move |_: &Theme, status| {
-                                match status {
-                                    _ => {
-                                        if self.rest{
-                                            style_button(get_rgb_color(255, 0, 0), Color::WHITE, radius)
-                                        } else {
-                                            style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
-                                        }
-                                    }
+                            move |_: &Theme, _| {
+                                if self.rest{
+                                    style_button(get_rgb_color(255, 0, 0), Color::WHITE, radius)
+                                } else {
+                                    style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
                                 }
                             })
                         )} 
@@ -198,6 +431,13 @@ impl ToDo {
                         else {
                             None
                         }).spacing(10)
+                .push_maybe(
+                    if !self.add && !self.reset {
+                        Some(button(if self.pomodoro.is_some() {"Stop Pomodoro"} else {"Pomodoro"}).on_press(Message::Pomodoro))
+                    } else {
+                        None
+                    }
+                )
                 .push(horizontal_space())
                 .push(button(if !self.reset {"Reset"} else {"Cancel"}).on_press(if !self.reset {Message::Reset(false)} else {Message::Cancel}).style(
                     move |_: &Theme, status| {
@@ -230,6 +470,20 @@ impl ToDo {
                     }
                 )
             )
+            .push(
+                button("Save As").on_press(Message::SaveAs).style(
+                        move |_: &Theme, status| {
+                            match status {
+                                button::Status::Hovered => {
+                                    style_button(get_rgb_color(0, 180, 0), Color::WHITE, radius)
+                                }
+                                _ => {
+                                    style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
+                                }
+                            }
+                    }
+                )
+            )
             .push(
                 button("Open").on_press(Message::Open).style(
                         move |_: &Theme, status| {
@@ -244,96 +498,341 @@ impl ToDo {
                     }
                 )
             )
+            .push(button("Undo").on_press_maybe(if self.history.can_undo() || self.add {Some(Message::Undo)} else {None}))
+            .push(button("Redo").on_press_maybe(if self.history.can_redo() {Some(Message::Redo)} else {None}))
+            .push(
+                button("Settings").on_press(Message::OpenSettings).style(
+                        move |_: &Theme, status| {
+                            match status {
+                                button::Status::Hovered => {
+                                    style_button(get_rgb_color(0, 180, 0), Color::WHITE, radius)
+                                }
+                                _ => {
+                                    style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
+                                }
+                            }
+                    }
+                )
+            )
+            .push(
+                button("Stats").on_press(Message::Show(Screen::Stats)).style(
+                        move |_: &Theme, status| {
+                            match status {
+                                button::Status::Hovered => {
+                                    style_button(get_rgb_color(0, 180, 0), Color::WHITE, radius)
+                                }
+                                _ => {
+                                    style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
+                                }
+                            }
+                    }
+                )
+            )
+            .push(
+                button("Report").on_press(Message::Show(Screen::Report)).style(
+                        move |_: &Theme, status| {
+                            match status {
+                                button::Status::Hovered => {
+                                    style_button(get_rgb_color(0, 180, 0), Color::WHITE, radius)
+                                }
+                                _ => {
+                                    style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
+                                }
+                            }
+                    }
+                )
+            )
+            .push(
+                button("Archive").on_press(Message::Show(Screen::Archive)).style(
+                        move |_: &Theme, status| {
+                            match status {
+                                button::Status::Hovered => {
+                                    style_button(get_rgb_color(0, 180, 0), Color::WHITE, radius)
+                                }
+                                _ => {
+                                    style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
+                                }
+                            }
+                    }
+                )
+            )
         );
 
         // Text for ToDo List Including Task Count, Clock, and other text
         main = main.push(text("To Do List: ").size(20)).push(text(&self.clock).size(16)).push(text("").size(10));
         main = main.push(Row::with_children(vec![text(format!("Tasks Completed: {}", self.complete)).into(), text(format!("Tasks Removed: {}", self.removed)).into()]).spacing(20));
+        if let Some(alerts) = self.alerts_view() {
+            main = main.push(alerts);
+        }
+        if let Some(pomodoro) = self.pomodoro_view() {
+            main = main.push(pomodoro);
+        }
+        if let Some(idle) = self.idle_view() {
+            main = main.push(idle);
+        }
+        if let Some(gap) = self.gap_view() {
+            main = main.push(gap);
+        }
+        if let Some(error) = &self.error {
+            main = main.push(
+                Row::new().push(text(error).size(16).color(get_rgb_color(220, 8, 51)))
+                    .push(button("Dismiss").on_press(Message::DismissError))
+                    .spacing(10).align_y(Alignment::Center)
+            );
+        }
+        if let Some(notice) = &self.notice {
+            main = main.push(
+                Row::new().push(text(&notice.text).size(16))
+                    .push(button("Undo").on_press(Message::Undo))
+                    .spacing(10).align_y(Alignment::Center)
+            );
+        }
 
         // Section for all Tasks
-        let mut tasks: Column<'_, Message> = Column::new().align_x(Alignment::Center).width(Length::Fill).padding(20).spacing(10).into();
-        for (index, task) in self.tasks.clone().into_iter().enumerate(){
-            if index != self.tasks.len() - 1 {
-                tasks = tasks.push(
-                    Container::new(
-                        Row::new().push(button("Complete").on_press(Message::RemoveTask(index as i32, true)))
-                        .push(text(task).size(16))
-                        .push(horizontal_space())
-                        .push(button("Remove").on_press(Message::RemoveTask(index as i32, false))
-                            .style(
-                                move |_: &Theme, status| {
-                                    match status {
-                                        button::Status::Active => {
-                                            style_button(get_rgb_color(255, 0, 0), Color::WHITE, radius)
-                                        }
-                                        _ => {
-                                            style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
-                                        },
-                                    }
-                                }
-                            )
-                        ).spacing(20).width(Length::Fill)
-                    )
-                );
-            } else if !self.add && index == self.tasks.len() - 1 {
-                // Last row, Special condition to make sure the new task being added doesn't show up until confirm button is clicked
-                tasks = tasks.push(
-                    Container::new(
-                        Row::new().push(button("Complete").on_press(Message::RemoveTask(index as i32, true)))
-                        .push(text(task).size(16))
-                        .push(horizontal_space())
-                        .push(button("Remove").on_press(Message::RemoveTask(index as i32, false))
-                            .style(
-                                move |_: &Theme, status| {
-                                    match status {
-                                        button::Status::Active => {
-                                            style_button(get_rgb_color(255, 0, 0), Color::WHITE, radius)
-                                        }
-                                        _ => {
-                                            style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
-                                        },
-                                    }
-                                }
-                            )
-                        ).spacing(20).width(Length::Fill)
-                    )
-                );
-            }
+        let mut tasks: Column<'_, Message> = Column::new().align_x(Alignment::Center).width(Length::Fill).padding(20).spacing(10);
+        for (index, task) in self.visible_tasks() {
+            tasks = tasks.push(self.task_row(index, task));
         }
         main = main.push(text("").size(12));
+        main = main.push(self.lists_view());
+        main = main.push(self.filter_view());
         if self.add {
-            main = main.push(Container::new(
-                text_input("New Task ...", &self.tasks[self.tasks.len() - 1])
+            main = main.push(Row::new()
+                .push(text_input("New Task ...", &self.tasks[self.tasks.len() - 1].title)
                     .on_input(|content:String | Message::AddTask(content, (self.tasks.len() - 1) as i32))
-                    .on_submit(Message::End)));
+                    .on_submit(Message::End))
+                .push(pick_list(task::Priority::ALL, Some(self.tasks[self.tasks.len() - 1].priority), Message::NewPriority))
+                .push(text_input("Due dd/mm/yyyy hh:mm", &self.new_due)
+                    .on_input(Message::NewDue)
+                    .on_submit(Message::End)
+                    .width(Length::Fixed(170.0)))
+                .push(text_input("Repeat e.g. weekly mon,thu", &self.new_repeat)
+                    .on_input(Message::NewRepeat)
+                    .on_submit(Message::End)
+                    .width(Length::Fixed(200.0)))
+                .push(text_input("Tags, comma separated", &self.new_tags)
+                    .on_input(Message::NewTags)
+                    .on_submit(Message::End)
+                    .width(Length::Fixed(180.0)))
+                .push_maybe(if edit::parse_due(&self.new_due).is_some() {None} else {Some(text("Enter a due date as dd/mm/yyyy hh:mm").size(14).color(get_rgb_color(220, 8, 51)))})
+                .push_maybe(if edit::parse_repeat(&self.new_repeat).is_some() {None} else {Some(text(edit::REPEAT_HINT).size(14).color(get_rgb_color(220, 8, 51)))})
+                .spacing(10).align_y(Alignment::Center));
         } else {
             main = main.push(text("").size(12))
         }
         main = main.push(Scrollable::new(tasks));
         main = main.push(vertical_space());
         // Times for tasks and time spent on breaks stored at the bottom row
-        main = main.push(Row::with_children(vec![text(format!("Current Task: {}", self.current_task.to_string())).into(), 
-                        text(format!("Last Task: {}", self.last_task.to_string())).into(), 
-                        text(format!("Break Time: {}", self.break_time.to_string())).color(if self.rest {Color::from_rgb(255.0, 0.0, 0.0)} else {Color::from_rgb(255.0, 255.0, 255.0)}).into()]).spacing(20));
+        main = main.push(Row::with_children(vec![text(format!("Current Task: {}", self.all_tasks().find(|task| Some(task.id) == self.active).map(|task| task.time.to_string()).unwrap_or_else(|| Time::default().to_string()))).into(), 
+                        text(format!("Last Task: {}", self.last_task)).into(), 
+                        text(format!("Break Time: {}", self.break_time)).color(if self.rest {Color::from_rgb(255.0, 0.0, 0.0)} else {Color::from_rgb(255.0, 255.0, 255.0)}).into()]).spacing(20));
         
        
         main.into()
     }
 
+    // Builds a single row of the task list, including its own timer
+    fn task_row<'a>(&'a self, index: usize, task: &'a task::Task) -> Element<'a, Message> {
+        let radius = 2;
+        let active = self.active == Some(task.id);
+        let target = self.drag.as_ref().is_some_and(|drag| drag.over == index);
+        let focused = self.focused == Some(task.id);
+        // Overdue tasks show in red, tasks due today in orange
+        let now = self.time.naive_local();
+        let due_color = task.due.and_then(|due| {
+            if due.is_overdue(now) {Some(get_rgb_color(220, 8, 51))} else if due.is_today(now) {Some(get_rgb_color(255, 140, 0))} else {None}
+        });
+        let done = task.subtasks.iter().filter(|subtask| subtask.done).count();
+        let expanded = self.expanded == Some(task.id);
+        let row = Container::new(Column::new().push(
+            Row::new()
+            // Handle for dragging the task to another place in the list
+            .push_maybe(if self.filter.sort == filter::SortMode::Manual {
+                Some(mouse_area(text("≡").size(20)).on_press(Message::Drag(task.id))
+                    .interaction(if self.drag.is_some() {mouse::Interaction::Grabbing} else {mouse::Interaction::Grab}))
+            } else {
+                None
+            })
+            .push(button("Complete").on_press_maybe(if self.can_complete(task) {Some(Message::RemoveTask(index as i32, true))} else {None}))
+            .push(button(if active {"Active"} else {"Start"}).on_press(Message::Select(task.id))
+                .style(
+                    move |_: &Theme, _| {
+                        if active {
+                            style_button(get_rgb_color(0, 155, 0), Color::WHITE, radius)
+                        } else {
+                            style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
+                        }
+                    }
+                )
+            )
+            .push(match &self.editing {
+                Some(editing) if editing.id == task.id => self.edit_view(editing),
+                // Clicking the title starts editing it
+                _ => button(text(&task.title).size(16)).padding(0).on_press(Message::Edit(task.id))
+                    .style(move |theme: &Theme, status| {
+                        let style = button::text(theme, status);
+                        button::Style { text_color: due_color.unwrap_or(style.text_color), ..style }
+                    }).into(),
+            })
+            .push_maybe(task.priority.color().map(|(r, g, b)| {
+                button(text(task.priority.to_string()).size(12)).padding([2, 6])
+                    .style(move |_: &Theme, _| style_from_rgb_button(r, g, b, Color::WHITE, radius))
+            }))
+            .push_maybe(task.due.map(|due| text(format!("Due {}", due)).size(14).color_maybe(due_color)))
+            .push_maybe(task.recurrence.as_ref().map(|recurrence| text(format!("Repeats {}", recurrence)).size(14)))
+            // Clicking a tag shows only the tasks carrying it
+            .extend(task.tags.iter().map(|tag| {
+                button(text(format!("#{}", tag)).size(12)).padding([2, 6]).on_press(Message::TagFilter(filter::TagFilter::Tag(tag.clone())))
+                    .style(move |_: &Theme, _| style_from_rgba_button(0, 155, 155, 200, Color::WHITE, radius))
+                    .into()
+            }))
+            // Checklist progress, opens and closes the notes and subtasks
+            .push(button(text(if task.subtasks.is_empty() {"Details".to_owned()} else {format!("{}/{}", done, task.subtasks.len())}).size(14))
+                .padding([2, 6]).on_press(Message::Expand(task.id)))
+            .push(horizontal_space())
+            .push_maybe(if task.pomodoros > 0 {Some(text(format!("Pomodoros: {}", task.pomodoros)).size(14))} else {None})
+            .push(text(task.time.to_string()).size(16))
+            .push(button("Remove").on_press(Message::RemoveTask(index as i32, false))
+                .style(
+                    move |_: &Theme, status| {
+                        match status {
+                            button::Status::Active => {
+                                style_button(get_rgb_color(255, 0, 0), Color::WHITE, radius)
+                            }
+                            _ => {
+                                style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
+                            },
+                        }
+                    }
+                )
+            ).spacing(20).width(Length::Fill).align_y(Alignment::Center)
+        ).push_maybe(if expanded {Some(self.notes_view(task))} else {None})
+        .push_maybe(if expanded {Some(self.subtasks_view(task))} else {None}).spacing(10)
+        ).style(move |_: &Theme| {
+            // Outlines the place the dragged task will be dropped
+            if target {
+                container::Style::default().border(Border::default().color(get_rgb_color(0, 155, 0)).width(2).rounded(radius))
+            } else if focused {
+                container::Style::default().border(Border::default().color(get_rgb_color(51, 89, 218)).width(1).rounded(radius))
+            } else {
+                container::Style::default()
+            }
+        });
+        // Clicking the row outside its buttons focuses it
+        let area = mouse_area(row).on_press(Message::Focus(task.id));
+        if self.drag.is_some() {area.on_enter(Message::DragOver(index)).into()} else {area.into()}
+    }
+
+    fn active_task_mut(&mut self) -> Option<&mut task::Task> {
+        let active = self.active?;
+        self.all_tasks_mut().find(|task| task.id == active)
+    }
+
+    // The active task counts unless a break is pausing it or nobody is around
+    fn task_running(&self) -> bool {
+        (!self.rest || !self.config.break_pauses_task) && !self.is_idle()
+    }
+
+    // Saves to the open file, or the auto-save file when nothing has been saved yet
+    fn quick_save(&self) -> Task<Message> {
+        let path = self.current_file.clone().unwrap_or_else(|| self.saves_dir().join("auto_save.json"));
+        self.save_to(path, Message::AutoSave)
+    }
+
+    // Starts or ends a break, pausing the task timer while it runs
+    fn toggle_break(&mut self) {
+        if !self.rest{
+            // Start break + Add current task time to old_dur
+            if self.config.break_pauses_task && let Some(task) = self.active_task_mut() {
+                task.time.swap_current();
+            }
+            self.rest = true;
+            self.break_time.new_start();
+        } else {
+            self.rest = false;
+            if !self.sleep {
+                self.break_time.swap_current();
+            } 
+            self.break_time.new_start();
+            self.sleep = false;
+            if self.config.break_pauses_task && let Some(task) = self.active_task_mut() {
+                task.time.new_start();
+            }
+        }
+    }
+
+    // Pauses the running task and starts the timer of the newly selected one
+    fn set_active(&mut self, id: Option<u64>) {
+        let running = self.task_running();
+        if running && let Some(task) = self.active_task_mut() {
+            task.time.swap_current();
+        }
+        self.active = id;
+        if running && let Some(task) = self.active_task_mut() {
+            task.time.new_start();
+        }
+    }
+
+    // Commits the task being added to the history, a freshly added task starts timing if no other task is running
+    // Recorded only once the task is confirmed and without it, so changes made while typing stay in order
+    fn confirm_new(&mut self) {
+        // Stays open until the due date and repeat rule read, like editing does
+        if !self.new_valid() {
+            return;
+        }
+        let snapshot = self.snapshot();
+        self.add = false;
+        self.history.push(snapshot);
+        if self.active.is_none() {
+            self.set_active(self.tasks.last().map(|task| task.id));
+        }
+    }
+
+    fn new_valid(&self) -> bool {
+        edit::parse_due(&self.new_due).is_some() && edit::parse_repeat(&self.new_repeat).is_some()
+    }
+
+    fn cancel_new(&mut self) {
+        self.add = false;
+        self.tasks.pop();
+    }
+
     pub fn update(&mut self, message:Message) -> Task<Message>{
+        let task = self.handle(message);
+        self.refresh_notes();
+        task
+    }
+
+    fn handle(&mut self, message:Message) -> Task<Message>{
         match message {
             Message::Reset(time_only) => {
                 if self.reset {
+                    self.record();
+                    self.notice = Some(Notice::new(if time_only {"Times reset"} else {"All tasks reset"}));
+                    // Every list is cleared, just as every list's times are reset
                     if !time_only {
                         self.tasks =  Vec::new();
+                        for list in self.lists.iter_mut() {
+                            list.tasks.clear();
+                            list.complete = 0;
+                            list.removed = 0;
+                        }
                         self.add = false;
                         self.complete = 0;
                         self.removed = 0;
                         self.rest = false;
+                        self.active = None;
+                    }
+                    // Timers start over, so their alerts can go off again
+                    self.fired.break_alert = false;
+                    self.fired.long_task = false;
+                    for task in self.all_tasks_mut() {
+                        task.time = Time::new(Instant::now());
+                        task.log.rebase(Duration::ZERO);
                     }
-                    self.current_task = Time::new(Instant::now());
                     self.last_task = Time::new(Instant::now());
                     self.break_time = Time::new(Instant::now());
+                    self.break_log.rebase(Duration::ZERO);
                     self.reset = false;
                 } else {
                     self.reset = true;
@@ -342,88 +841,329 @@ impl ToDo {
             },
             Message::New => {
                 if self.add {
-                    self.add = false; 
+                    self.confirm_new();
                 } else {
                     self.add = true;
-                    self.tasks.push(String::new());
+                    self.new_due = String::new();
+                    self.new_repeat = String::new();
+                    self.new_tags = String::new();
+                    self.tasks.push(task::Task::new(self.next_id, String::new()));
+                    self.next_id += 1;
                 }
                 Task::none()
             }
             Message::Cancel => {
-                if self.add {
-                    self.add = false;
-                    self.tasks.remove(self.tasks.len() - 1);
+                if self.editing.is_some() {
+                    self.editing = None;
+                } else if self.notes_editor.is_some() {
+                    self.notes_editor = None;
+                } else if self.drag.is_some() {
+                    self.drag = None;
+                } else if self.add {
+                    self.cancel_new();
                 } else if self.reset {
                     self.reset = false;
                 }
                 Task::none()
             }
             Message::End => {
-                self.add = false;
+                self.confirm_new();
                 Task::none()
             }
             Message::AddTask(task, index) => {
-                self.tasks[index as usize] = task;
+                self.tasks[index as usize].title = task;
+                Task::none()
+            },
+            Message::Edit(id) => {
+                self.start_edit(id)
+            },
+            Message::EditTitle(title) => {
+                if let Some(editing) = &mut self.editing {
+                    editing.title = title;
+                }
+                Task::none()
+            },
+            Message::EditPriority(priority) => {
+                if let Some(editing) = &mut self.editing {
+                    editing.priority = priority;
+                }
+                Task::none()
+            },
+            Message::NewPriority(priority) => {
+                if self.add && let Some(task) = self.tasks.last_mut() {
+                    task.priority = priority;
+                }
+                Task::none()
+            },
+            Message::EditDue(due) => {
+                if let Some(editing) = &mut self.editing {
+                    editing.due = due;
+                }
+                Task::none()
+            },
+            Message::NewDue(due) => {
+                if self.add && let Some(task) = self.tasks.last_mut() {
+                    task.due = edit::parse_due(&due).flatten();
+                }
+                self.new_due = due;
+                Task::none()
+            },
+            Message::EditRepeat(repeat) => {
+                if let Some(editing) = &mut self.editing {
+                    editing.repeat = repeat;
+                }
+                Task::none()
+            },
+            Message::NewRepeat(repeat) => {
+                if self.add && let Some(task) = self.tasks.last_mut() {
+                    task.recurrence = edit::parse_repeat(&repeat).flatten();
+                }
+                self.new_repeat = repeat;
+                Task::none()
+            },
+            Message::Expand(id) => {
+                self.toggle_expanded(id);
+                Task::none()
+            },
+            Message::SubtaskText(title) => {
+                self.new_subtask = title;
+                Task::none()
+            },
+            Message::AddSubtask => {
+                self.add_subtask();
+                Task::none()
+            },
+            Message::ToggleSubtask(id, index) => {
+                self.toggle_subtask(id, index);
+                Task::none()
+            },
+            Message::RemoveSubtask(id, index) => {
+                self.remove_subtask(id, index);
+                Task::none()
+            },
+            Message::EditNotes(id) => {
+                self.edit_notes(id);
+                Task::none()
+            },
+            Message::NotesAction(action) => {
+                if let Some(editor) = &mut self.notes_editor {
+                    editor.content.perform(action);
+                }
+                Task::none()
+            },
+            Message::SaveNotes => {
+                self.save_notes();
+                Task::none()
+            },
+            Message::LinkClicked(url) => {
+                self.open_link(url);
+                Task::none()
+            },
+            Message::EditTags(tags) => {
+                if let Some(editing) = &mut self.editing {
+                    editing.tags = tags;
+                }
+                Task::none()
+            },
+            Message::NewTags(tags) => {
+                if self.add && let Some(task) = self.tasks.last_mut() {
+                    task.tags = lists::parse_tags(&tags);
+                }
+                self.new_tags = tags;
+                Task::none()
+            },
+            Message::TagFilter(tag) => {
+                self.filter.tag = tag;
+                Task::none()
+            },
+            Message::SwitchList(index) => {
+                self.switch_list(index);
+                Task::none()
+            },
+            Message::NewListName(name) => {
+                self.new_list = name;
+                Task::none()
+            },
+            Message::AddList => {
+                self.add_list();
+                Task::none()
+            },
+            Message::RemoveList => {
+                self.remove_list();
+                Task::none()
+            },
+            Message::ConfirmEdit => {
+                self.confirm_edit();
+                Task::none()
+            },
+            Message::Drag(id) => {
+                self.start_drag(id);
+                Task::none()
+            },
+            Message::DragOver(index) => {
+                self.drag_over(index);
+                Task::none()
+            },
+            Message::Drop => {
+                self.drop_task();
+                Task::none()
+            },
+            Message::MoveUp => {
+                self.move_focused(true);
+                Task::none()
+            },
+            Message::MoveDown => {
+                self.move_focused(false);
+                Task::none()
+            },
+            Message::Focus(id) => {
+                self.focus(id);
+                Task::none()
+            },
+            Message::FocusUp => {
+                self.focus_step(true);
+                Task::none()
+            },
+            Message::FocusDown => {
+                self.focus_step(false);
+                Task::none()
+            },
+            Message::Sort(sort) => {
+                self.filter.sort = sort;
+                Task::none()
+            },
+            Message::StatusFilter(status) => {
+                self.filter.status = status;
+                Task::none()
+            },
+            Message::Search(search) => {
+                self.filter.search = search;
                 Task::none()
             },
             Message::RemoveTask(task_num, completed) => {
-                // Remove task from Vec
-                self.tasks.remove(task_num as usize);
+                if completed && !self.can_complete(&self.tasks[task_num as usize]) {
+                    return Task::none();
+                }
+                self.record();
+                self.notice = Some(Notice::new(if completed {"Task completed"} else {"Task removed"}));
 
-                // Caculate the total time it took for the task + move to last_time
-                self.last_task.copy(&mut self.current_task);
+                // Remove task from Vec
+                let mut task = self.tasks.remove(task_num as usize);
+                task.finish(completed);
+                if completed {
+                    for subtask in task.subtasks.iter_mut() {
+                        subtask.done = true;
+                    }
+                }
+                if self.expanded == Some(task.id) {
+                    self.expanded = None;
+                    self.notes_editor = None;
+                }
+                if self.editing.as_ref().is_some_and(|editing| editing.id == task.id) {
+                    self.editing = None;
+                }
 
-                // Reset current task time
-                self.current_task = Time::new(Instant::now());
+                // Freeze the task's total time + move to last_time
+                if self.active == Some(task.id) {
+                    self.last_task.copy(&mut task.time);
+                    self.active = None;
+                }
+                // Recurring tasks come back in the same place
+                if completed && let Some(next) = self.next_instance(&task) {
+                    self.tasks.insert(task_num as usize, next);
+                }
+                self.archive_task(task);
 
                 if completed {
                     self.complete += 1;
                 } else {
                     self.removed += 1;
                 }
+                if completed && self.config.save_on_complete {
+                    self.quick_save()
+                } else {
+                    Task::none()
+                }
+            },
+            Message::Select(id) => {
+                self.set_active(if self.active == Some(id) {None} else {Some(id)});
+                Task::none()
+            },
+            Message::Restore(id) => {
+                self.record();
+                self.restore_task(id);
+                Task::none()
+            },
+            Message::Show(screen) => {
+                self.screen = screen;
+                Task::none()
+            },
+            Message::ArchiveFilter(filter) => {
+                self.archive_filter = filter;
+                Task::none()
+            },
+            Message::Undo => {
+                self.undo();
+                Task::none()
+            },
+            Message::Redo => {
+                self.redo();
                 Task::none()
             },
             Message::Tick => {
                 if Local::now() != self.time {
                     self.time = Local::now();
-                    self.clock = self.time.format("%d/%m/%Y %H:%M:%S").to_string();
-                    if !self.rest {
-                        self.current_task.tick();
-                    } else {
-                        if !self.sleep {
-                            self.break_time.tick();
+                    self.clock = self.time.format(&self.config.clock_pattern()).to_string();
+                    if let Some(notice) = &mut self.notice {
+                        notice.ticks = notice.ticks.saturating_sub(1);
+                        if notice.ticks == 0 {
+                            self.notice = None;
                         }
                     }
+                    if self.task_running() && let Some(task) = self.active_task_mut() {
+                        task.time.tick();
+                    }
+                    if self.rest && !self.sleep {
+                        self.break_time.tick();
+                    }
+                    // Whatever was added since the last Tick goes to today in the timesheet
+                    let today = self.time.date_naive();
+                    for task in self.all_tasks_mut() {
+                        task.log.sync(task.time.export_time(), today);
+                    }
+                    self.break_log.sync(self.break_time.export_time(), today);
+                    self.tick_pomodoro();
+                    self.check_idle();
+                    let alerts = self.check_alerts();
 
                     self.tick_count += 1;
-                    if self.tick_count == 120 && !self.sleep && self.auto_save{
-                        // let _ = Task::perform(save_to_file(format!("{}/saves/{}", env!("CARGO_MANIFEST_DIR"), format!("auto{}_save.json", self.time.format("%H_%M").to_string())).into(), self.clone()), |result| Message::FileSave(result));
-                        Task::perform(save_to_file(format!("{}/saves/", env!("CARGO_MANIFEST_DIR")).into(), "auto_save.json".to_owned(), self.clone()), |result| Message::FileSave(result))
+                    if self.tick_count >= self.config.auto_save_interval && !self.sleep && self.config.auto_save{
+                        self.tick_count = 0;
+                        // Same file as save-on-complete, so the file reopened at startup has the latest work
+                        Task::batch([alerts, self.quick_save()])
                     } else {
-                        Task::none()
+                        alerts
                     }
                 } else {
                     Task::none()
                 }
             },
             Message::Break => {
-                if !self.rest{
-                    // Start break + Add current task time to old_dur
-                    self.rest = true;
-                    self.current_task.swap_current();
-                    self.break_time.new_start();
+                self.record();
+                // In a pomodoro cycle the break button skips ahead to the next phase
+                if self.pomodoro.is_some() {
+                    self.next_phase(false);
                 } else {
-                    self.rest = false;
-                    if !self.sleep {
-                        self.break_time.swap_current();
-                    } 
-                    self.break_time.new_start();
-                    self.sleep = false;
-                    self.current_task.new_start();
+                    self.toggle_break();
                 }
                 Task::none()
             },
+            Message::Pomodoro => {
+                self.toggle_pomodoro();
+                Task::none()
+            },
             Message::Sleep => {
+                self.record();
                 if self.sleep {
                     if self.rest {
                         self.break_time.new_start();
@@ -437,24 +1177,166 @@ impl ToDo {
                 Task::none()
             },
             Message::Save => {
-                // println!("Recieved Save Message");
-                Task::perform(save_to_file(format!("{}/saves/", env!("CARGO_MANIFEST_DIR")).into(), "saves.json".to_owned(),self.clone()), |result| Message::FileSave(result))
+                match &self.current_file {
+                    Some(path) => self.save_to(path.clone(), Message::FileSave),
+                    None => self.open_browser(Mode::SaveAs),
+                }
+            },
+            Message::SaveAs => {
+                self.open_browser(Mode::SaveAs)
             },
             Message::Open => {
-                // println!("Recieved Save Message");
-                Task::perform(read_from_file(format!("{}/saves/", env!("CARGO_MANIFEST_DIR")).into(), "saves.json".to_owned()), |result| Message::FileOpen(result))
+                self.open_browser(Mode::Open)
             },
-            Message::FileSave(result) => {
+            Message::BrowseDirectory(directory) => {
+                self.browser.directory = directory;
+                Task::none()
+            },
+            Message::BrowseTo(directory) => {
+                self.browse_to(directory)
+            },
+            Message::Refresh => {
+                self.refresh_browser()
+            },
+            Message::Listed(result) => {
+                match result {
+                    Ok(listing) => {
+                        self.browser.listing = listing;
+                        self.browser.error = None;
+                    },
+                    Err(err) => {
+                        self.browser.listing = fs::Listing::default();
+                        self.browser.error = Some(format!("Could not read directory: {}", err));
+                    },
+                }
+                Task::none()
+            },
+            Message::BackupsListed(result) => {
                 match result {
-                    Ok(_) => {},
-                    Err(err) => {eprintln!("File Save failed {}", err)},
+                    Ok(backups) => self.browser.backups = backups,
+                    Err(err) => eprintln!("Listing backups failed {}", err),
                 }
                 Task::none()
+            },
+            Message::FileName(name) => {
+                self.browser.file_name = name;
+                Task::none()
+            },
+            Message::PickFile(path) => {
+                self.pick_file(path)
+            },
+            Message::ConfirmSaveAs => {
+                self.confirm_save_as()
+            },
+            Message::AutoSave(result) => {
+                if let Err(err) = result {
+                    eprintln!("Auto Save failed {}", err);
+                }
+                Task::none()
+            },
+            Message::FileSave(result) => {
+                match result {
+                    Ok(path) => {
+                        self.current_file = Some(path.clone());
+                        self.push_recent(path)
+                    },
+                    Err(err) => {
+                        eprintln!("File Save failed {}", err);
+                        self.error = Some(format!("File Save failed: {}", err));
+                        Task::none()
+                    },
+                }
             }
             Message::FileOpen(result) => {
                 match result {
-                    Ok(new) => {self.replace(new)},
-                    Err(err) => {eprintln!("File Load failed {}", err)}
+                    Ok((path, new)) => {
+                        self.record();
+                        self.error = None;
+                        self.replace(*new);
+                        if path.starts_with(self.backups_dir()) {
+                            // Saving over a backup would lose it on the next prune, so ask for a new file instead
+                            self.notice = Some(Notice::new("Backup restored"));
+                            self.current_file = None;
+                            Task::none()
+                        } else {
+                            self.notice = Some(Notice::new("Save loaded"));
+                            self.current_file = Some(path.clone());
+                            self.push_recent(path)
+                        }
+                    },
+                    Err(err) => {
+                        eprintln!("File Load failed {}", err);
+                        self.error = Some(format!("File Load failed: {}", err));
+                        Task::none()
+                    }
+                }
+            }
+            Message::ResolveGap(keep) => {
+                self.record();
+                self.resolve_gap(keep);
+                Task::none()
+            }
+            Message::OpenSettings => {
+                self.open_settings();
+                Task::none()
+            }
+            Message::Setting(change) => {
+                self.change_setting(change)
+            }
+            Message::ConfigSaved(result) => {
+                if let Err(err) = result {
+                    eprintln!("Config Save failed {}", err);
+                    self.error = Some(format!("Config Save failed: {}", err));
+                }
+                Task::none()
+            }
+            Message::DismissError => {
+                self.error = None;
+                Task::none()
+            }
+            Message::DismissAlert(index) => {
+                if index < self.alerts.len() {
+                    self.alerts.remove(index);
+                }
+                Task::none()
+            }
+            Message::Activity => {
+                self.mark_activity();
+                Task::none()
+            }
+            Message::ResolveIdle(choice) => {
+                self.record();
+                self.resolve_idle(choice);
+                Task::none()
+            }
+            Message::ReportPeriod(period) => {
+                self.report.period = period;
+                Task::none()
+            }
+            Message::ReportDate(date) => {
+                self.report.date = date;
+                Task::none()
+            }
+            Message::ExportReport(format) => {
+                self.export_report(format)
+            }
+            Message::ReportExported(result) => {
+                match result {
+                    Ok(path) => self.notice = Some(Notice::new(format!("Report saved to {}", path.display()))),
+                    Err(err) => {
+                        eprintln!("Report Export failed {}", err);
+                        self.error = Some(format!("Report Export failed: {}", err));
+                    }
+                }
+                Task::none()
+            }
+            Message::StatsRange(range) => {
+                self.stats_range = range;
+                Task::none()
+            }
+            Message::Notified(result) => {
+                if let Err(err) = result {
+                    eprintln!("Desktop notification failed {}", err);
                 }
                 Task::none()
             }
@@ -462,23 +1344,63 @@ impl ToDo {
     }
 
     pub fn subscription(&self) -> Subscription<Message>{
-        iced::time::every(std::time::Duration::from_secs(1)).map(|_| Message::Tick)
+        Subscription::batch([
+            iced::time::every(std::time::Duration::from_secs(1)).map(|_| Message::Tick),
+            keyboard::on_key_press(handle_key),
+            iced::event::listen_with(idle::activity),
+            iced::event::listen_with(edit::escape),
+            iced::event::listen_with(reorder::release),
+        ])
     }
 
 
 
 }
 
-fn format_duration(duration: Duration) -> String {
-    let secs = duration.as_secs();
-    let hours = secs / 3600;
-    let minutes = (secs % 3600) / 60;
-    let seconds = secs % 60;
-
-    format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+// Global keyboard shortcuts
+fn handle_key(key: keyboard::Key, modifiers: keyboard::Modifiers) -> Option<Message> {
+    match key.as_ref() {
+        keyboard::Key::Character("z") if modifiers.command() && modifiers.shift() => Some(Message::Redo),
+        keyboard::Key::Character("z") if modifiers.command() => Some(Message::Undo),
+        keyboard::Key::Character("y") if modifiers.command() => Some(Message::Redo),
+        keyboard::Key::Named(keyboard::key::Named::ArrowUp) if modifiers.alt() => Some(Message::MoveUp),
+        keyboard::Key::Named(keyboard::key::Named::ArrowDown) if modifiers.alt() => Some(Message::MoveDown),
+        keyboard::Key::Named(keyboard::key::Named::ArrowUp) if modifiers.is_empty() => Some(Message::FocusUp),
+        keyboard::Key::Named(keyboard::key::Named::ArrowDown) if modifiers.is_empty() => Some(Message::FocusDown),
+        _ => None,
+    }
 }
 
-
 fn main() -> iced::Result<> {
-    iced::application("To Do List", ToDo::update, ToDo::view).subscription(ToDo::subscription).run()
+    let args = match cli::Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    let data_dir = config::resolve_data_dir(args.data_dir.clone());
+    let config = Config::load(&data_dir);
+    if let Some(period) = args.report {
+        if let Err(err) = report::run_cli(&args, period, &data_dir, &config) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    iced::application(ToDo::title, ToDo::update, ToDo::view).subscription(ToDo::subscription).run_with(move || {
+        let todo = ToDo::new(data_dir, config);
+        // Writes the config out on first run so it can be edited by hand
+        let mut startup = vec![todo.store_config()];
+        // Picks up where the last session left off
+        if let Some(last) = todo.config.recent_files.first().filter(|last| last.exists()) {
+            startup.push(Task::perform(fs::read_from_file(last.clone()), Message::FileOpen));
+        }
+        (todo, Task::batch(startup))
+    })
 }
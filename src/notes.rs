@@ -0,0 +1,130 @@
+use std::fmt;
+
+use iced::{Alignment, Element, Length, Theme};
+use iced::widget::{button, markdown, text, text_editor, Column, Row};
+
+use crate::task::Task;
+use crate::{Message, ToDo};
+
+// Links notes may open, notes can come from any loaded file so nothing else is launched
+const LINK_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+// Notes being written for a task, the editor content has to be copied by hand
+pub struct NotesEditor {
+    pub id: u64,
+    pub content: text_editor::Content,
+}
+
+impl NotesEditor {
+    // The editor always ends its text with a newline
+    fn notes(&self) -> String {
+        self.content.text().trim_end().to_owned()
+    }
+}
+
+impl Clone for NotesEditor {
+    fn clone(&self) -> Self {
+        Self { id: self.id, content: text_editor::Content::with_text(&self.content.text()) }
+    }
+}
+
+impl PartialEq for NotesEditor {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.content.text() == other.content.text()
+    }
+}
+
+impl fmt::Debug for NotesEditor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotesEditor").field("id", &self.id).field("notes", &self.notes()).finish()
+    }
+}
+
+// Parsed Markdown of the open task's notes, kept so the view can borrow it
+#[derive(Debug, Clone)]
+pub struct RenderedNotes {
+    pub id: u64,
+    pub source: String,
+    pub items: Vec<markdown::Item>,
+}
+
+impl PartialEq for RenderedNotes {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.source == other.source
+    }
+}
+
+impl ToDo {
+    pub fn edit_notes(&mut self, id: u64) {
+        let Some(task) = self.tasks.iter().find(|task| task.id == id) else {return};
+        self.notes_editor = Some(NotesEditor { id, content: text_editor::Content::with_text(&task.notes) });
+    }
+
+    pub fn save_notes(&mut self) {
+        let Some(editor) = self.notes_editor.take() else {return};
+        let notes = editor.notes();
+        if self.tasks.iter().any(|task| task.id == editor.id && task.notes != notes) {
+            self.record();
+            if let Some(task) = self.tasks.iter_mut().find(|task| task.id == editor.id) {
+                task.notes = notes;
+            }
+        }
+    }
+
+    // Re-parses the open task's notes whenever they no longer match what was rendered
+    pub fn refresh_notes(&mut self) {
+        let Some(task) = self.expanded.and_then(|id| self.tasks.iter().find(|task| task.id == id)) else {
+            self.rendered_notes = None;
+            return;
+        };
+        if self.rendered_notes.as_ref().is_some_and(|rendered| rendered.id == task.id && rendered.source == task.notes) {
+            return;
+        }
+        self.rendered_notes = Some(RenderedNotes { id: task.id, source: task.notes.clone(), items: markdown::parse(&task.notes).collect() });
+    }
+
+    // Web and mail links in notes open in the desktop's default handler
+    pub fn open_link(&mut self, url: markdown::Url) {
+        if !LINK_SCHEMES.contains(&url.scheme()) {
+            self.error = Some(format!("Only web and mail links can be opened, not {}", url));
+            return;
+        }
+        match std::process::Command::new("xdg-open").arg(url.as_str()).spawn() {
+            // Waited on in the background so the finished process is reaped
+            Ok(mut child) => {
+                std::thread::spawn(move || child.wait());
+            },
+            Err(err) => self.error = Some(format!("Could not open {}: {}", url, err)),
+        }
+    }
+
+    pub fn notes_view<'a>(&'a self, task: &'a Task) -> Element<'a, Message> {
+        let mut notes: Column<'_, Message> = Column::new().spacing(5).padding([0, 40]);
+
+        if let Some(editor) = self.notes_editor.as_ref().filter(|editor| editor.id == task.id) {
+            notes = notes.push(
+                text_editor(&editor.content)
+                    .placeholder("Notes, Markdown is supported ...")
+                    .on_action(Message::NotesAction)
+                    .height(Length::Fixed(160.0))
+            );
+            return notes.push(
+                Row::new()
+                    .push(button("Save Notes").on_press(Message::SaveNotes))
+                    .push(button("Cancel").on_press(Message::Cancel))
+                    .spacing(10).align_y(Alignment::Center)
+            ).into();
+        }
+
+        match self.rendered_notes.as_ref().filter(|rendered| rendered.id == task.id && !rendered.source.trim().is_empty()) {
+            Some(rendered) => {
+                notes = notes.push(
+                    markdown::view(&rendered.items, markdown::Settings::default(), markdown::Style::from_palette(Theme::default().palette()))
+                        .map(Message::LinkClicked)
+                );
+            },
+            None => notes = notes.push(text("No notes yet").size(14)),
+        }
+        notes.push(button("Edit Notes").on_press(Message::EditNotes(task.id))).into()
+    }
+}
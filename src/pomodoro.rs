@@ -0,0 +1,111 @@
+use iced::{Alignment, Element};
+use iced::widget::{text, Row};
+
+use crate::styles::*;
+use crate::time::format_duration;
+use crate::{Message, ToDo};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Phase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl Phase {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Phase::Work => "Work",
+            Phase::ShortBreak => "Short Break",
+            Phase::LongBreak => "Long Break",
+        }
+    }
+}
+
+// Countdown for the current pomodoro phase, counted down once per Tick
+#[derive(Debug, PartialEq, Clone)]
+pub struct Pomodoro {
+    pub phase: Phase,
+    pub remaining: u32,
+    // Work phases finished since the cycle was started
+    pub cycles: u32,
+}
+
+impl ToDo {
+    fn phase_length(&self, phase: Phase) -> u32 {
+        let minutes = match phase {
+            Phase::Work => self.config.pomodoro_work,
+            Phase::ShortBreak => self.config.pomodoro_short_break,
+            Phase::LongBreak => self.config.pomodoro_long_break,
+        };
+        minutes.max(1) * 60
+    }
+
+    // Starts a cycle with a work phase, ending any break that is running
+    pub fn toggle_pomodoro(&mut self) {
+        if self.pomodoro.take().is_some() {
+            return;
+        }
+        if self.rest {
+            self.toggle_break();
+        }
+        self.pomodoro = Some(Pomodoro {
+            phase: Phase::Work,
+            remaining: self.phase_length(Phase::Work),
+            cycles: 0,
+        });
+    }
+
+    // Counts the running phase down, switching between work and breaks when it runs out
+    pub fn tick_pomodoro(&mut self) {
+        // Time away from the computer doesn't count towards a phase
+        if self.sleep || self.is_idle() {
+            return;
+        }
+        let Some(pomodoro) = &mut self.pomodoro else {return};
+        pomodoro.remaining = pomodoro.remaining.saturating_sub(1);
+        if pomodoro.remaining == 0 {
+            self.next_phase(true);
+        }
+    }
+
+    // Moves on to the next phase, only a finished work phase counts as a pomodoro
+    pub fn next_phase(&mut self, finished: bool) {
+        let Some(pomodoro) = &self.pomodoro else {return};
+        let (phase, mut cycles) = (pomodoro.phase, pomodoro.cycles);
+        let next = match phase {
+            Phase::Work => {
+                if finished {
+                    cycles += 1;
+                    if let Some(task) = self.active_task_mut() {
+                        task.pomodoros += 1;
+                    }
+                }
+                if finished && cycles % self.config.pomodoro_long_every.max(1) == 0 {Phase::LongBreak} else {Phase::ShortBreak}
+            },
+            Phase::ShortBreak | Phase::LongBreak => Phase::Work,
+        };
+        // Breaks in the cycle use the normal break timer
+        if (next == Phase::Work) == self.rest {
+            self.toggle_break();
+        }
+        self.pomodoro = Some(Pomodoro {
+            phase: next,
+            remaining: self.phase_length(next),
+            cycles,
+        });
+    }
+
+    pub fn pomodoro_view(&self) -> Option<Element<'_, Message>> {
+        let pomodoro = self.pomodoro.as_ref()?;
+        let color = if pomodoro.phase == Phase::Work {get_rgb_color(220, 8, 51)} else {get_rgb_color(0, 155, 0)};
+        Some(
+            Row::new()
+                .push(text(pomodoro.phase.label()).size(18).color(color))
+                .push(text(format_duration(std::time::Duration::from_secs(pomodoro.remaining.into()))).size(18))
+                .push(text(format!("Pomodoros: {}", pomodoro.cycles)).size(16))
+                .spacing(20).align_y(Alignment::Center)
+                .into()
+        )
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::task::{Due, Subtask, Task};
+use crate::ToDo;
+
+// How often a task comes back once it is completed
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(tag = "every", rename_all = "lowercase")]
+pub enum Recurrence {
+    Daily,
+    Weekdays,
+    Weekly { days: Vec<Weekday> },
+    // The day of the month it falls on, taken from the first due date when not given
+    Monthly {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        day: Option<u32>,
+    },
+    Days { count: u32 },
+}
+
+impl Recurrence {
+    // Reads the same text Display writes, e.g. "weekly mon,thu", "monthly on 31" or "every 3 days"
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase();
+        let words: Vec<&str> = value.split_whitespace().collect();
+        match words.as_slice() {
+            ["daily"] => Some(Recurrence::Daily),
+            ["weekdays"] => Some(Recurrence::Weekdays),
+            ["monthly"] => Some(Recurrence::Monthly { day: None }),
+            ["monthly", "on", day] => day.parse::<u32>().ok().filter(|day| (1..=31).contains(day)).map(|day| Recurrence::Monthly { day: Some(day) }),
+            ["weekly", days @ ..] if !days.is_empty() => {
+                let days = days.join(",").split(',').filter(|day| !day.is_empty()).map(|day| day.parse::<Weekday>().ok()).collect::<Option<Vec<_>>>()?;
+                if days.is_empty() {None} else {Some(Recurrence::Weekly { days })}
+            },
+            ["every", count, "day" | "days"] => count.parse::<u32>().ok().filter(|count| *count > 0).map(|count| Recurrence::Days { count }),
+            _ => None,
+        }
+    }
+
+    // First day after the given one that the task falls on
+    pub fn next_after(&self, day: NaiveDate) -> NaiveDate {
+        match self {
+            Recurrence::Daily => day + Days::new(1),
+            Recurrence::Weekdays => {
+                let mut next = day + Days::new(1);
+                while matches!(next.weekday(), Weekday::Sat | Weekday::Sun) {
+                    next = next + Days::new(1);
+                }
+                next
+            },
+            Recurrence::Weekly { days } => {
+                (1..=7).map(|offset| day + Days::new(offset)).find(|next| days.contains(&next.weekday())).unwrap_or(day + Days::new(7))
+            },
+            // Short months clamp to their last day, the month after goes back to the anchor day
+            Recurrence::Monthly { day: anchor } => {
+                let anchor = anchor.unwrap_or(day.day());
+                let Some(month) = day.with_day(1).and_then(|first| first.checked_add_months(Months::new(1))) else {return day + Days::new(30)};
+                let last = month.checked_add_months(Months::new(1)).and_then(|next| next.pred_opt()).map_or(28, |last| last.day());
+                month.with_day(anchor.min(last)).unwrap_or(month)
+            },
+            Recurrence::Days { count } => day + Days::new(u64::from((*count).max(1))),
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekdays => write!(f, "weekdays"),
+            Recurrence::Weekly { days } => write!(f, "weekly {}", days.iter().map(|day| day.to_string().to_lowercase()).collect::<Vec<_>>().join(",")),
+            Recurrence::Monthly { day: None } => write!(f, "monthly"),
+            Recurrence::Monthly { day: Some(day) } => write!(f, "monthly on {}", day),
+            Recurrence::Days { count } => write!(f, "every {} days", count),
+        }
+    }
+}
+
+impl ToDo {
+    // Builds the next instance of a completed recurring task, due on the next day it falls on after today
+    pub fn next_instance(&mut self, task: &Task) -> Option<Task> {
+        let mut recurrence = task.recurrence.clone()?;
+        let today = self.time.date_naive();
+        let start = task.due.map(|due| due.date).unwrap_or(today);
+        // Monthly tasks keep the day they started on, so a short month doesn't move them for good
+        if let Recurrence::Monthly { day: day @ None } = &mut recurrence {
+            *day = Some(start.day());
+        }
+        let mut date = recurrence.next_after(start);
+        // Catches up on occurrences missed while the task sat overdue
+        while date <= today {
+            date = recurrence.next_after(date);
+        }
+
+        let mut next = Task::new(self.next_id, task.title.clone());
+        self.next_id += 1;
+        next.notes = task.notes.clone();
+        next.priority = task.priority;
+        next.due = Some(Due { date, time: task.due.and_then(|due| due.time) });
+        next.recurrence = Some(recurrence);
+        // The checklist starts over each time
+        next.subtasks = task.subtasks.iter().map(|subtask| Subtask { title: subtask.title.clone(), done: false }).collect();
+        next.tags = task.tags.clone();
+        Some(next)
+    }
+}
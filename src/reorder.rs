@@ -0,0 +1,89 @@
+use iced::{event, mouse, Event};
+
+use crate::filter::SortMode;
+use crate::{Message, ToDo};
+
+// Task being dragged by its handle and the row the cursor is over
+#[derive(Debug, PartialEq, Clone)]
+pub struct Drag {
+    pub id: u64,
+    pub over: usize,
+}
+
+// The button can be let go anywhere in the window, so the drop is picked up whatever the event status
+pub fn release(event: Event, _status: event::Status, _window: iced::window::Id) -> Option<Message> {
+    match event {
+        Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => Some(Message::Drop),
+        _ => None,
+    }
+}
+
+impl ToDo {
+    // The task being added always stays last, so nothing moves while adding,
+    // and a sorted list doesn't show the order being changed
+    fn can_move(&self) -> bool {
+        !self.add && self.filter.sort == SortMode::Manual
+    }
+
+    pub fn start_drag(&mut self, id: u64) {
+        if !self.can_move() {
+            return;
+        }
+        let Some(over) = self.tasks.iter().position(|task| task.id == id) else {return};
+        self.focused = Some(id);
+        self.drag = Some(Drag { id, over });
+    }
+
+    pub fn drag_over(&mut self, index: usize) {
+        if let Some(drag) = &mut self.drag {
+            drag.over = index;
+        }
+    }
+
+    pub fn drop_task(&mut self) {
+        let Some(drag) = self.drag.take() else {return};
+        if let Some(from) = self.tasks.iter().position(|task| task.id == drag.id) {
+            self.move_task(from, drag.over);
+        }
+    }
+
+    // Row picked by clicking, editing or the arrow keys, so moving doesn't need the timer started
+    pub fn focus(&mut self, id: u64) {
+        self.focused = Some(id);
+    }
+
+    // Steps the focus through the rows as they are shown, starting from the top or bottom
+    pub fn focus_step(&mut self, up: bool) {
+        let visible: Vec<u64> = self.visible_tasks().into_iter().map(|(_, task)| task.id).collect();
+        let current = visible.iter().position(|id| Some(*id) == self.focused);
+        let next = match current {
+            Some(index) if up => index.checked_sub(1),
+            Some(index) => Some(index + 1),
+            None if up => visible.len().checked_sub(1),
+            None => Some(0),
+        };
+        if let Some(id) = next.and_then(|index| visible.get(index)) {
+            self.focused = Some(*id);
+        }
+    }
+
+    // Keyboard alternative to dragging, moves the focused task one place, or the active one when nothing is focused
+    pub fn move_focused(&mut self, up: bool) {
+        if !self.can_move() {
+            return;
+        }
+        let Some(id) = self.focused.or(self.active) else {return};
+        let Some(from) = self.tasks.iter().position(|task| task.id == id) else {return};
+        let to = if up {from.saturating_sub(1)} else {from + 1};
+        self.move_task(from, to);
+    }
+
+    fn move_task(&mut self, from: usize, to: usize) {
+        if from == to || to >= self.tasks.len() {
+            return;
+        }
+        self.record();
+        let task = self.tasks.remove(from);
+        self.tasks.insert(to, task);
+    }
+}
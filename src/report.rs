@@ -0,0 +1,258 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::io;
+
+use chrono::{Datelike, Days, Local, NaiveDate};
+use iced::{Alignment, Element, Font, Length, Task};
+use iced::widget::{button, horizontal_space, text, text_input, Column, Row, Scrollable};
+
+use crate::cli::Args;
+use crate::config::Config;
+use crate::fs::{get_todo, write_atomic};
+use crate::styles::*;
+use crate::time::format_duration;
+use crate::{Message, Screen, ToDo};
+
+const DATE_FORMAT: &str = "%d/%m/%Y";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Period {
+    Day,
+    Week,
+}
+
+impl Period {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "day" => Some(Period::Day),
+            "week" => Some(Period::Week),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    Csv,
+    Markdown,
+    Html,
+}
+
+impl Format {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "csv" => Some(Format::Csv),
+            "md" | "markdown" => Some(Format::Markdown),
+            "html" => Some(Format::Html),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Markdown => "md",
+            Format::Html => "html",
+        }
+    }
+}
+
+// Report screen inputs
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReportState {
+    pub period: Period,
+    pub date: String,
+}
+
+impl Default for ReportState {
+    fn default() -> Self {
+        Self {
+            period: Period::Day,
+            date: Local::now().format(DATE_FORMAT).to_string(),
+        }
+    }
+}
+
+// Tracked time per task for each day of a period, plus break totals
+#[derive(Debug, PartialEq, Clone)]
+pub struct Timesheet {
+    pub title: String,
+    pub days: Vec<NaiveDate>,
+    pub rows: Vec<(String, Vec<Duration>)>,
+    pub breaks: Vec<Duration>,
+}
+
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), DATE_FORMAT).ok()
+}
+
+impl Timesheet {
+    pub fn new(todo: &ToDo, period: Period, date: NaiveDate) -> Self {
+        let (start, length, title) = match period {
+            Period::Day => (date, 1, format!("Timesheet for {}", date.format(DATE_FORMAT))),
+            Period::Week => {
+                let monday = date - Days::new(date.weekday().num_days_from_monday().into());
+                (monday, 7, format!("Timesheet for the week of {}", monday.format(DATE_FORMAT)))
+            },
+        };
+        let days: Vec<NaiveDate> = (0..length).map(|offset| start + Days::new(offset)).collect();
+
+        // Open tasks first, then finished ones, leaving out anything without time in the period
+        let rows = todo.all_tasks().chain(todo.archive.iter())
+            .map(|task| (task.title.clone(), days.iter().map(|day| task.log.on(*day)).collect::<Vec<_>>()))
+            .filter(|(_, times)| times.iter().any(|time| !time.is_zero()))
+            .collect();
+        let breaks = days.iter().map(|day| todo.break_log.on(*day)).collect();
+        Self { title, days, rows, breaks }
+    }
+
+    // Task time per day, without breaks
+    fn totals(&self) -> Vec<Duration> {
+        (0..self.days.len()).map(|index| self.rows.iter().map(|(_, times)| times[index]).sum()).collect()
+    }
+
+    // Header, task rows, then the task and break totals, each with a trailing total column
+    fn table(&self) -> Vec<Vec<String>> {
+        let mut header = vec!["Task".to_owned()];
+        header.extend(self.days.iter().map(|day| day.format("%a %d/%m").to_string()));
+        header.push("Total".to_owned());
+
+        let line = |label: &str, times: &[Duration]| {
+            let mut line = vec![label.to_owned()];
+            line.extend(times.iter().map(|time| format_duration(*time)));
+            line.push(format_duration(times.iter().sum()));
+            line
+        };
+        let mut table = vec![header];
+        table.extend(self.rows.iter().map(|(title, times)| line(title, times)));
+        table.push(line("Tasks Total", &self.totals()));
+        table.push(line("Breaks", &self.breaks));
+        table
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        let table = self.table();
+        match format {
+            Format::Csv => table.iter().map(|line| line.iter().map(|cell| csv_escape(cell)).collect::<Vec<_>>().join(",")).collect::<Vec<_>>().join("\n") + "\n",
+            Format::Markdown => {
+                let mut out = format!("# {}\n\n", self.title);
+                for (index, line) in table.iter().enumerate() {
+                    out += &format!("| {} |\n", line.iter().map(|cell| cell.replace('|', "\\|")).collect::<Vec<_>>().join(" | "));
+                    if index == 0 {
+                        out += &format!("|{}\n", "---|".repeat(line.len()));
+                    }
+                }
+                out
+            },
+            Format::Html => {
+                let mut out = format!("<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body>\n<h1>{0}</h1>\n<table>\n", html_escape(&self.title));
+                for (index, line) in table.iter().enumerate() {
+                    let cell = if index == 0 {"th"} else {"td"};
+                    out += &format!("<tr>{}</tr>\n", line.iter().map(|value| format!("<{0}>{1}</{0}>", cell, html_escape(value))).collect::<String>());
+                }
+                out + "</table>\n</body>\n</html>\n"
+            },
+        }
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn html_escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Handles --report: loads a save, prints the timesheet or writes it to --output
+pub fn run_cli(args: &Args, period: Period, data_dir: &Path, config: &Config) -> Result<(), String> {
+    let file = args.file.clone()
+        .or_else(|| config.recent_files.first().cloned())
+        .unwrap_or_else(|| data_dir.join("saves").join("auto_save.json"));
+    let contents = std::fs::read_to_string(&file).map_err(|error| format!("Could not read {}: {}", file.display(), error))?;
+    let todo = get_todo(&contents).map_err(|error| format!("Could not load {}: {}", file.display(), error))?;
+    let date = match &args.date {
+        Some(date) => parse_date(date).ok_or_else(|| format!("Invalid date {}, expected dd/mm/yyyy", date))?,
+        None => Local::now().date_naive(),
+    };
+
+    let report = Timesheet::new(&todo, period, date).render(args.format.unwrap_or(Format::Markdown));
+    match &args.output {
+        Some(output) => std::fs::write(output, report).map_err(|error| format!("Could not write {}: {}", output.display(), error)),
+        None => {
+            print!("{}", report);
+            Ok(())
+        },
+    }
+}
+
+async fn export(path: PathBuf, contents: String) -> Result<PathBuf, io::ErrorKind> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await.map_err(|error| error.kind())?;
+    }
+    write_atomic(&path, contents.as_bytes()).await.map_err(|error| error.kind())?;
+    Ok(path)
+}
+
+impl ToDo {
+    fn timesheet(&self) -> Option<Timesheet> {
+        parse_date(&self.report.date).map(|date| Timesheet::new(self, self.report.period, date))
+    }
+
+    // Writes the report into the reports directory, named after the period it covers
+    pub fn export_report(&self, format: Format) -> Task<Message> {
+        let Some(timesheet) = self.timesheet() else {return Task::none()};
+        let name = format!("timesheet-{}-{}.{}", if self.report.period == Period::Day {"day"} else {"week"}, timesheet.days[0].format("%Y-%m-%d"), format.extension());
+        Task::perform(export(self.data_dir.join("reports").join(name), timesheet.render(format)), Message::ReportExported)
+    }
+
+    pub fn report_view(&self) -> Element<'_, Message> {
+        let mut main: Column<'_, Message> = Column::new().width(Length::Fill).padding(20).spacing(10);
+
+        main = main.push(
+            Row::new()
+                .push(button("Back").on_press(Message::Show(Screen::Tasks)))
+                .push(text("Timesheet").size(20))
+                .push(horizontal_space())
+                .push(button("Day").on_press_maybe(if self.report.period == Period::Day {None} else {Some(Message::ReportPeriod(Period::Day))}))
+                .push(button("Week").on_press_maybe(if self.report.period == Period::Week {None} else {Some(Message::ReportPeriod(Period::Week))}))
+                .push(text_input("dd/mm/yyyy", &self.report.date)
+                    .on_input(Message::ReportDate)
+                    .width(Length::Fixed(140.0)))
+                .spacing(10).align_y(Alignment::Center)
+        );
+
+        let Some(timesheet) = self.timesheet() else {
+            main = main.push(text("Enter a date as dd/mm/yyyy").size(14).color(get_rgb_color(220, 8, 51)));
+            return main.into();
+        };
+
+        main = main.push(
+            Row::new()
+                .push(text("Export as"))
+                .push(button("CSV").on_press(Message::ExportReport(Format::Csv)))
+                .push(button("Markdown").on_press(Message::ExportReport(Format::Markdown)))
+                .push(button("HTML").on_press(Message::ExportReport(Format::Html)))
+                .spacing(10).align_y(Alignment::Center)
+        );
+        main = main.push(text(timesheet.title.clone()).size(18));
+
+        let mut table: Column<'_, Message> = Column::new().spacing(5);
+        for line in timesheet.table() {
+            let mut row: Row<'_, Message> = Row::new().spacing(10);
+            for (index, cell) in line.into_iter().enumerate() {
+                row = row.push(text(cell).font(Font::MONOSPACE).width(if index == 0 {Length::Fixed(220.0)} else {Length::Fixed(90.0)}));
+            }
+            table = table.push(row);
+        }
+        main = main.push(Scrollable::new(table).direction(iced::widget::scrollable::Direction::Both {
+            vertical: Default::default(),
+            horizontal: Default::default(),
+        }));
+        main.into()
+    }
+}
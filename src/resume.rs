@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use iced::{Alignment, Element};
+use iced::widget::{button, text, Row};
+
+use crate::time::format_duration;
+use crate::{Message, ToDo};
+
+// Gaps shorter than this are counted without asking
+const GAP_PROMPT: Duration = Duration::from_secs(60);
+
+// What was running when the save was written
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Activity {
+    Task(u64),
+    Break,
+}
+
+// Time that passed between saving and loading while something was running
+#[derive(Debug, PartialEq, Clone)]
+pub struct Gap {
+    pub activity: Activity,
+    pub duration: Duration,
+}
+
+impl Gap {
+    pub fn needs_prompt(&self) -> bool {
+        self.duration >= GAP_PROMPT
+    }
+}
+
+impl ToDo {
+    // Either adds the time the app was closed to whatever was running, or drops it
+    pub fn resolve_gap(&mut self, keep: bool) {
+        let Some(gap) = self.gap.take() else {return};
+        if !keep {
+            return;
+        }
+        match gap.activity {
+            Activity::Task(id) => {
+                if let Some(task) = self.all_tasks_mut().find(|task| task.id == id) {
+                    task.time.add(gap.duration);
+                }
+            },
+            Activity::Break => self.break_time.add(gap.duration),
+        }
+    }
+
+    pub fn gap_view(&self) -> Option<Element<'_, Message>> {
+        let gap = self.gap.as_ref()?;
+        let activity = match gap.activity {
+            Activity::Task(id) => self.all_tasks().find(|task| task.id == id).map(|task| format!("\"{}\"", task.title)).unwrap_or_else(|| "a task".to_owned()),
+            Activity::Break => "your break".to_owned(),
+        };
+        Some(
+            Row::new()
+                .push(text(format!("The app was closed for {} while {} was running.", format_duration(gap.duration), activity)).size(16))
+                .push(button("Count it").on_press(Message::ResolveGap(true)))
+                .push(button("Discard").on_press(Message::ResolveGap(false)))
+                .spacing(10).align_y(Alignment::Center)
+                .into()
+        )
+    }
+}
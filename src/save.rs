@@ -0,0 +1,421 @@
+use chrono::prelude::{DateTime, Local};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::error::LoadError;
+use crate::lists::{TaskList, DEFAULT_LIST};
+use crate::resume::{Activity, Gap};
+use crate::recur::Recurrence;
+use crate::task::{Due, Priority, Subtask, Task, TaskStatus};
+use crate::time::{DayLog, Time};
+use crate::ToDo;
+
+// Bump this whenever the layout of SaveFile changes, and add a migration below
+pub const SAVE_VERSION: u32 = 2;
+
+// Durations are stored as [seconds, milliseconds]
+mod duration_format {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        (duration.as_secs(), duration.subsec_millis()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let (secs, millis) = <(u64, u64)>::deserialize(deserializer)?;
+        Ok(Duration::from_secs(secs) + Duration::from_millis(millis))
+    }
+}
+
+// Per-day time is stored as whole seconds keyed by date
+mod days_format {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(days: &BTreeMap<NaiveDate, Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        days.iter().map(|(day, time)| (*day, time.as_secs())).collect::<BTreeMap<_, _>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<NaiveDate, Duration>, D::Error> {
+        let days = BTreeMap::<NaiveDate, u64>::deserialize(deserializer)?;
+        Ok(days.into_iter().map(|(day, secs)| (day, Duration::from_secs(secs))).collect())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskRecord {
+    pub id: u64,
+    pub title: String,
+    #[serde(default)]
+    pub notes: String,
+    pub created: DateTime<Local>,
+    #[serde(default)]
+    pub completed: Option<DateTime<Local>>,
+    pub status: TaskStatus,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub due: Option<Due>,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    #[serde(default)]
+    pub subtasks: Vec<Subtask>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub list: Option<String>,
+    #[serde(with = "duration_format", default)]
+    pub time: Duration,
+    #[serde(default)]
+    pub pomodoros: u32,
+    #[serde(with = "days_format", default)]
+    pub daily: BTreeMap<NaiveDate, Duration>,
+}
+
+impl From<&Task> for TaskRecord {
+    fn from(task: &Task) -> Self {
+        Self {
+            id: task.id,
+            title: task.title.clone(),
+            notes: task.notes.clone(),
+            created: task.created,
+            completed: task.completed,
+            status: task.status,
+            priority: task.priority,
+            due: task.due,
+            recurrence: task.recurrence.clone(),
+            subtasks: task.subtasks.clone(),
+            tags: task.tags.clone(),
+            list: task.list.clone(),
+            time: task.time.export_time(),
+            pomodoros: task.pomodoros,
+            daily: task.log.days.clone(),
+        }
+    }
+}
+
+impl From<TaskRecord> for Task {
+    fn from(record: TaskRecord) -> Self {
+        let mut task = Task::new(record.id, record.title);
+        task.notes = record.notes;
+        task.created = record.created;
+        task.completed = record.completed;
+        task.status = record.status;
+        task.priority = record.priority;
+        task.due = record.due;
+        task.recurrence = record.recurrence;
+        task.subtasks = record.subtasks;
+        task.tags = record.tags;
+        task.list = record.list;
+        task.time = Time::from(record.time);
+        task.pomodoros = record.pomodoros;
+        task.log = DayLog::from_days(record.daily, record.time);
+        task
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListRecord {
+    pub name: String,
+    #[serde(default)]
+    pub completed: u32,
+    #[serde(default)]
+    pub removed: u32,
+    #[serde(default)]
+    pub tasks: Vec<TaskRecord>,
+}
+
+// Current on-disk layout, new fields need a serde default so older saves still load
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveFile {
+    pub version: u32,
+    pub lists: Vec<ListRecord>,
+    // Index into lists of the list that was shown
+    #[serde(default)]
+    pub current_list: usize,
+    #[serde(default)]
+    pub archive: Vec<TaskRecord>,
+    #[serde(default)]
+    pub active: Option<u64>,
+    #[serde(with = "duration_format")]
+    pub break_time: Duration,
+    #[serde(with = "duration_format")]
+    pub prev_task: Duration,
+    #[serde(default)]
+    pub clock: Option<ClockState>,
+    #[serde(with = "days_format", default)]
+    pub break_log: BTreeMap<NaiveDate, Duration>,
+}
+
+// Wall clock state of the timers when the file was written, so time spent closed can be recovered
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClockState {
+    pub saved_at: DateTime<Local>,
+    pub rest: bool,
+    pub sleep: bool,
+    // When the timer that was running at save time last started
+    pub started_at: Option<DateTime<Local>>,
+}
+
+impl ClockState {
+    fn from_todo(todo: &ToDo) -> Self {
+        let started_at = if todo.rest && !todo.sleep {
+            Some(todo.break_time.started_at())
+        } else if !todo.rest {
+            todo.all_tasks().find(|task| Some(task.id) == todo.active).map(|task| task.time.started_at())
+        } else {
+            None
+        };
+        Self {
+            saved_at: Local::now(),
+            rest: todo.rest,
+            sleep: todo.sleep,
+            started_at,
+        }
+    }
+
+    fn gap(&self, active: Option<u64>) -> Option<Gap> {
+        // Only the part of the gap after the running stretch started is credited to it
+        let since = self.started_at?.max(self.saved_at);
+        let activity = if self.rest {Activity::Break} else {Activity::Task(active?)};
+        let duration = (Local::now() - since).to_std().ok()?;
+        Some(Gap { activity, duration })
+    }
+}
+
+impl From<&ToDo> for SaveFile {
+    fn from(todo: &ToDo) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            lists: todo.lists.iter().enumerate().map(|(index, list)| {
+                if index == todo.current_list {
+                    ListRecord { name: list.name.clone(), completed: todo.complete, removed: todo.removed, tasks: todo.settled_tasks().iter().map(TaskRecord::from).collect() }
+                } else {
+                    ListRecord { name: list.name.clone(), completed: list.complete, removed: list.removed, tasks: list.tasks.iter().map(TaskRecord::from).collect() }
+                }
+            }).collect(),
+            current_list: todo.current_list,
+            archive: todo.archive.iter().map(TaskRecord::from).collect(),
+            active: todo.active,
+            break_time: todo.break_time.export_time(),
+            prev_task: todo.last_task.export_time(),
+            clock: Some(ClockState::from_todo(todo)),
+            break_log: todo.break_log.days.clone(),
+        }
+    }
+}
+
+impl SaveFile {
+    pub fn into_todo(self) -> ToDo {
+        let mut lists: Vec<TaskList> = self.lists.into_iter().map(|list| TaskList {
+            name: list.name,
+            tasks: list.tasks.into_iter().map(Task::from).collect(),
+            complete: list.completed,
+            removed: list.removed,
+        }).collect();
+        if lists.is_empty() {
+            lists.push(TaskList::new(DEFAULT_LIST.to_owned()));
+        }
+        let current_list = self.current_list.min(lists.len() - 1);
+        let current = &mut lists[current_list];
+        let tasks = std::mem::take(&mut current.tasks);
+        let archive = self.archive.into_iter().map(Task::from).collect();
+        let mut todo = ToDo::from(tasks, archive, self.active, current.complete, current.removed, self.prev_task, self.break_time);
+        todo.lists = lists;
+        todo.current_list = current_list;
+        // Ids have to stay unique across every list
+        todo.next_id = todo.all_tasks().map(|task| task.id + 1).max().unwrap_or(0).max(todo.next_id);
+        todo.break_log = DayLog::from_days(self.break_log, self.break_time);
+        if let Some(clock) = self.clock {
+            todo.rest = clock.rest;
+            todo.sleep = clock.sleep;
+            todo.gap = clock.gap(self.active);
+        }
+        todo
+    }
+
+    // Reads any known version of the save format, upgrading it to the current one
+    pub fn from_value(value: Value) -> Result<Self, LoadError> {
+        if !value.is_object() {
+            return Err(LoadError::WrongType { field: ".".to_owned(), message: "expected an object".to_owned() });
+        }
+        let version = match value.get("version") {
+            None => 0,
+            Some(version) => version.as_u64().ok_or_else(|| LoadError::WrongType { field: "version".to_owned(), message: "expected an unsigned integer".to_owned() })?,
+        };
+        match version {
+            0 => {
+                let timed = value.get("tasks").and_then(Value::as_array).is_some_and(|tasks| tasks.iter().any(|task| task.get("time").is_some()));
+                let legacy: LegacySave = serde_path_to_error::deserialize(value).map_err(LoadError::data)?;
+                Ok(legacy.migrate(timed).migrate())
+            },
+            1 => {
+                let save: SaveFileV1 = serde_path_to_error::deserialize(value).map_err(LoadError::data)?;
+                Ok(save.migrate())
+            },
+            version if version == u64::from(SAVE_VERSION) => serde_path_to_error::deserialize(value).map_err(LoadError::data),
+            _ => Err(LoadError::UnsupportedVersion(version)),
+        }
+    }
+}
+
+// Version 1: a single list of tasks with its counters at the top level
+#[derive(Debug, Deserialize)]
+struct SaveFileV1 {
+    completed: u32,
+    removed: u32,
+    tasks: Vec<TaskRecord>,
+    #[serde(default)]
+    archive: Vec<TaskRecord>,
+    #[serde(default)]
+    active: Option<u64>,
+    #[serde(with = "duration_format")]
+    break_time: Duration,
+    #[serde(with = "duration_format")]
+    prev_task: Duration,
+    #[serde(default)]
+    clock: Option<ClockState>,
+    #[serde(with = "days_format", default)]
+    break_log: BTreeMap<NaiveDate, Duration>,
+}
+
+impl SaveFileV1 {
+    // The one list becomes the default list
+    fn migrate(self) -> SaveFile {
+        SaveFile {
+            version: SAVE_VERSION,
+            lists: vec![ListRecord { name: DEFAULT_LIST.to_owned(), completed: self.completed, removed: self.removed, tasks: self.tasks }],
+            current_list: 0,
+            archive: self.archive,
+            active: self.active,
+            break_time: self.break_time,
+            prev_task: self.prev_task,
+            clock: self.clock,
+            break_log: self.break_log,
+        }
+    }
+}
+
+// Tasks before version 1 were either plain strings or objects without a time
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum LegacyTask {
+    Title(String),
+    Record {
+        id: Option<u64>,
+        title: String,
+        #[serde(default)]
+        notes: String,
+        created: Option<DateTime<Local>>,
+        completed: Option<DateTime<Local>>,
+        status: Option<TaskStatus>,
+        #[serde(with = "duration_format", default)]
+        time: Duration,
+    },
+}
+
+impl LegacyTask {
+    fn id(&self) -> Option<u64> {
+        match self {
+            LegacyTask::Title(_) => None,
+            LegacyTask::Record { id, .. } => *id,
+        }
+    }
+
+    // Tasks without an id take the next free one
+    fn migrate(self, next_id: &mut u64) -> TaskRecord {
+        let id = self.id().unwrap_or_else(|| {
+            *next_id += 1;
+            *next_id - 1
+        });
+        match self {
+            LegacyTask::Title(title) => TaskRecord {
+                id,
+                title,
+                notes: String::new(),
+                created: Local::now(),
+                completed: None,
+                status: TaskStatus::Active,
+                priority: Priority::None,
+                due: None,
+                recurrence: None,
+                subtasks: Vec::new(),
+                tags: Vec::new(),
+                list: None,
+                time: Duration::ZERO,
+                pomodoros: 0,
+                daily: BTreeMap::new(),
+            },
+            LegacyTask::Record { title, notes, created, completed, status, time, .. } => TaskRecord {
+                id,
+                title,
+                notes,
+                created: created.unwrap_or_else(Local::now),
+                completed,
+                status: status.unwrap_or(TaskStatus::Active),
+                priority: Priority::None,
+                due: None,
+                recurrence: None,
+                subtasks: Vec::new(),
+                tags: Vec::new(),
+                list: None,
+                time,
+                pomodoros: 0,
+                daily: BTreeMap::new(),
+            },
+        }
+    }
+}
+
+// Version 0: completed, removed, tasks, break_time, cur_task, prev_task
+#[derive(Debug, Deserialize)]
+struct LegacySave {
+    #[serde(default)]
+    completed: u32,
+    #[serde(default)]
+    removed: u32,
+    #[serde(default)]
+    tasks: Vec<LegacyTask>,
+    #[serde(default)]
+    archive: Vec<LegacyTask>,
+    #[serde(default)]
+    active: Option<u64>,
+    #[serde(with = "duration_format")]
+    break_time: Duration,
+    #[serde(with = "duration_format")]
+    cur_task: Duration,
+    #[serde(with = "duration_format")]
+    prev_task: Duration,
+}
+
+impl LegacySave {
+    // Only the very first format lacks per-task times, its single running timer goes to the first task
+    fn migrate(self, timed: bool) -> SaveFileV1 {
+        // Missing ids are numbered after the highest given one, so they can't collide with it
+        let mut next_id = self.tasks.iter().chain(&self.archive).filter_map(LegacyTask::id).map(|id| id + 1).max().unwrap_or(0);
+        let mut tasks: Vec<TaskRecord> = self.tasks.into_iter().map(|task| task.migrate(&mut next_id)).collect();
+        let archive = self.archive.into_iter().map(|task| task.migrate(&mut next_id)).collect();
+        let mut active = self.active;
+        if active.is_none() && !timed && let Some(first) = tasks.first_mut() {
+            first.time = self.cur_task;
+            active = Some(first.id);
+        }
+        SaveFileV1 {
+            completed: self.completed,
+            removed: self.removed,
+            tasks,
+            archive,
+            active,
+            break_time: self.break_time,
+            prev_task: self.prev_task,
+            clock: None,
+            break_log: BTreeMap::new(),
+        }
+    }
+}
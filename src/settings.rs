@@ -0,0 +1,213 @@
+use iced::{Alignment, Element, Length, Task};
+use iced::widget::{button, checkbox, horizontal_space, text, text_input, Column, Row, Scrollable};
+
+use crate::config::valid_time_format;
+use crate::styles::*;
+use crate::{Message, Screen, ToDo};
+
+#[derive(Debug, Clone)]
+pub enum Change {
+    AutoSave(bool),
+    Interval(String),
+    SaveOnComplete(bool),
+    ClockFormat(String),
+    TwelveHour(bool),
+    BreakPausesTask(bool),
+    BackupCount(String),
+    PomodoroWork(String),
+    PomodoroShortBreak(String),
+    PomodoroLongBreak(String),
+    PomodoroLongEvery(String),
+    BreakAlert(String),
+    TaskAlert(String),
+    DesktopNotifications(bool),
+    IdleMinutes(String),
+    PinHighPriority(bool),
+    SubtasksBlockCompletion(bool),
+}
+
+// Text typed into the settings screen, only copied to the config once it is valid
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Draft {
+    pub interval: String,
+    pub clock_format: String,
+    pub backup_count: String,
+    pub pomodoro_work: String,
+    pub pomodoro_short_break: String,
+    pub pomodoro_long_break: String,
+    pub pomodoro_long_every: String,
+    pub break_alert: String,
+    pub task_alert: String,
+    pub idle_minutes: String,
+}
+
+// Positive whole numbers, or zero as well where it switches something off
+fn parse_count(value: &str, zero_ok: bool) -> Option<u32> {
+    value.trim().parse::<u32>().ok().filter(|count| zero_ok || *count > 0)
+}
+
+// Label, number input and unit on one line, with a hint while the input can't be used
+fn number_row<'a>(label: &'a str, value: &'a str, unit: &'a str, zero_ok: bool, change: fn(String) -> Change) -> Row<'a, Message> {
+    Row::new()
+        .push(text(label))
+        .push(text_input("", value)
+            .on_input(move |value| Message::Setting(change(value)))
+            .width(Length::Fixed(80.0)))
+        .push(text(unit))
+        .push_maybe(if parse_count(value, zero_ok).is_some() {None} else {Some(text("Enter a whole number").color(get_rgb_color(220, 8, 51)))})
+        .spacing(10).align_y(Alignment::Center)
+}
+
+impl ToDo {
+    pub fn open_settings(&mut self) {
+        self.draft = Draft {
+            interval: self.config.auto_save_interval.to_string(),
+            clock_format: self.config.clock_format.clone(),
+            backup_count: self.config.backup_count.to_string(),
+            pomodoro_work: self.config.pomodoro_work.to_string(),
+            pomodoro_short_break: self.config.pomodoro_short_break.to_string(),
+            pomodoro_long_break: self.config.pomodoro_long_break.to_string(),
+            pomodoro_long_every: self.config.pomodoro_long_every.to_string(),
+            break_alert: self.config.break_alert_minutes.to_string(),
+            task_alert: self.config.task_alert_minutes.to_string(),
+            idle_minutes: self.config.idle_minutes.to_string(),
+        };
+        self.screen = Screen::Settings;
+    }
+
+    pub fn change_setting(&mut self, change: Change) -> Task<Message> {
+        match change {
+            Change::AutoSave(enabled) => self.config.auto_save = enabled,
+            Change::Interval(interval) => {
+                if let Some(seconds) = parse_count(&interval, false) {
+                    self.config.auto_save_interval = seconds;
+                }
+                self.draft.interval = interval;
+            },
+            Change::SaveOnComplete(enabled) => self.config.save_on_complete = enabled,
+            Change::ClockFormat(format) => {
+                if valid_time_format(&format) {
+                    self.config.clock_format = format.clone();
+                }
+                self.draft.clock_format = format;
+            },
+            Change::TwelveHour(enabled) => self.config.twelve_hour = enabled,
+            Change::BreakPausesTask(enabled) => {
+                // Mid-break the task timer has to follow the new setting straight away
+                if self.rest && enabled != self.config.break_pauses_task && let Some(task) = self.active_task_mut() {
+                    if enabled {
+                        task.time.swap_current();
+                    } else {
+                        task.time.new_start();
+                    }
+                }
+                self.config.break_pauses_task = enabled;
+            },
+            Change::BackupCount(count) => {
+                if let Some(count) = parse_count(&count, true) {
+                    self.config.backup_count = count;
+                }
+                self.draft.backup_count = count;
+            },
+            Change::PomodoroWork(minutes) => {
+                if let Some(minutes) = parse_count(&minutes, false) {
+                    self.config.pomodoro_work = minutes;
+                }
+                self.draft.pomodoro_work = minutes;
+            },
+            Change::PomodoroShortBreak(minutes) => {
+                if let Some(minutes) = parse_count(&minutes, false) {
+                    self.config.pomodoro_short_break = minutes;
+                }
+                self.draft.pomodoro_short_break = minutes;
+            },
+            Change::PomodoroLongBreak(minutes) => {
+                if let Some(minutes) = parse_count(&minutes, false) {
+                    self.config.pomodoro_long_break = minutes;
+                }
+                self.draft.pomodoro_long_break = minutes;
+            },
+            Change::PomodoroLongEvery(count) => {
+                if let Some(count) = parse_count(&count, false) {
+                    self.config.pomodoro_long_every = count;
+                }
+                self.draft.pomodoro_long_every = count;
+            },
+            Change::BreakAlert(minutes) => {
+                if let Some(minutes) = parse_count(&minutes, true) {
+                    self.config.break_alert_minutes = minutes;
+                }
+                self.draft.break_alert = minutes;
+            },
+            Change::TaskAlert(minutes) => {
+                if let Some(minutes) = parse_count(&minutes, true) {
+                    self.config.task_alert_minutes = minutes;
+                }
+                self.draft.task_alert = minutes;
+            },
+            Change::DesktopNotifications(enabled) => self.config.desktop_notifications = enabled,
+            Change::IdleMinutes(minutes) => {
+                if let Some(minutes) = parse_count(&minutes, true) {
+                    self.config.idle_minutes = minutes;
+                }
+                self.draft.idle_minutes = minutes;
+            },
+            Change::PinHighPriority(enabled) => self.config.pin_high_priority = enabled,
+            Change::SubtasksBlockCompletion(enabled) => self.config.subtasks_block_completion = enabled,
+        }
+        self.store_config()
+    }
+
+    pub fn settings_view(&self) -> Element<'_, Message> {
+        let mut main: Column<'_, Message> = Column::new().width(Length::Fill).spacing(15);
+
+        main = main.push(
+            Row::new()
+                .push(button("Back").on_press(Message::Show(Screen::Tasks)))
+                .push(text("Settings").size(20))
+                .push(horizontal_space())
+                .spacing(10).align_y(Alignment::Center)
+        );
+
+        main = main.push(text("Saving").size(18));
+        main = main.push(checkbox("Auto-save", self.config.auto_save).on_toggle(|enabled| Message::Setting(Change::AutoSave(enabled))));
+        main = main.push(number_row("Auto-save every", &self.draft.interval, "seconds", false, Change::Interval));
+        main = main.push(checkbox("Save when a task is completed", self.config.save_on_complete).on_toggle(|enabled| Message::Setting(Change::SaveOnComplete(enabled))));
+        main = main.push(number_row("Keep", &self.draft.backup_count, "backups of each save", true, Change::BackupCount));
+
+        main = main.push(text("Clock").size(18));
+        main = main.push(
+            Row::new()
+                .push(text("Format"))
+                .push(text_input("%d/%m/%Y %H:%M:%S", &self.draft.clock_format)
+                    .on_input(|format| Message::Setting(Change::ClockFormat(format)))
+                    .width(Length::Fixed(240.0)))
+                .push_maybe(if valid_time_format(&self.draft.clock_format) {None} else {Some(text("Invalid format").color(get_rgb_color(220, 8, 51)))})
+                .spacing(10).align_y(Alignment::Center)
+        );
+        main = main.push(checkbox("12 hour clock", self.config.twelve_hour).on_toggle(|enabled| Message::Setting(Change::TwelveHour(enabled))));
+        main = main.push(text(format!("Preview: {}", self.time.format(&self.config.clock_pattern()))).size(14));
+
+        main = main.push(text("Timers").size(18));
+        main = main.push(checkbox("Breaks pause the task timer", self.config.break_pauses_task).on_toggle(|enabled| Message::Setting(Change::BreakPausesTask(enabled))));
+        main = main.push(number_row("Pause the task after", &self.draft.idle_minutes, "idle minutes (0 is off)", true, Change::IdleMinutes));
+
+        main = main.push(text("Tasks").size(18));
+        main = main.push(checkbox("Pin high and urgent tasks to the top", self.config.pin_high_priority).on_toggle(|enabled| Message::Setting(Change::PinHighPriority(enabled))));
+        main = main.push(checkbox("Tasks can't be completed until their subtasks are done, otherwise completing a task completes its subtasks", self.config.subtasks_block_completion)
+            .on_toggle(|enabled| Message::Setting(Change::SubtasksBlockCompletion(enabled))));
+
+        main = main.push(text("Pomodoro").size(18));
+        main = main.push(number_row("Work for", &self.draft.pomodoro_work, "minutes", false, Change::PomodoroWork));
+        main = main.push(number_row("Short breaks last", &self.draft.pomodoro_short_break, "minutes", false, Change::PomodoroShortBreak));
+        main = main.push(number_row("Long breaks last", &self.draft.pomodoro_long_break, "minutes", false, Change::PomodoroLongBreak));
+        main = main.push(number_row("Take a long break every", &self.draft.pomodoro_long_every, "pomodoros", false, Change::PomodoroLongEvery));
+
+        main = main.push(text("Alerts").size(18));
+        main = main.push(number_row("Alert when a break goes over", &self.draft.break_alert, "minutes (0 is off)", true, Change::BreakAlert));
+        main = main.push(number_row("Alert when a task goes over", &self.draft.task_alert, "minutes (0 is off)", true, Change::TaskAlert));
+        main = main.push(checkbox("Desktop notifications", self.config.desktop_notifications).on_toggle(|enabled| Message::Setting(Change::DesktopNotifications(enabled))));
+
+        Scrollable::new(main.padding(20)).into()
+    }
+}
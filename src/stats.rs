@@ -0,0 +1,186 @@
+use std::time::Duration;
+
+use chrono::{Days, Local, NaiveDate};
+use iced::{mouse, Alignment, Color, Element, Length, Point, Rectangle, Renderer, Size, Theme};
+use iced::widget::{button, canvas, horizontal_space, text, Column, Row, Scrollable};
+
+use crate::styles::buttons::*;
+use crate::styles::*;
+use crate::task::TaskStatus;
+use crate::time::format_duration;
+use crate::{Message, Screen, ToDo};
+
+// Ranges the dashboard can look back over, in days
+pub const RANGES: [u32; 3] = [7, 30, 90];
+
+// Figures for the days in a range, oldest day first
+#[derive(Debug, PartialEq, Clone)]
+pub struct Stats {
+    pub days: Vec<NaiveDate>,
+    pub completed: Vec<u32>,
+    pub tracked: Vec<Duration>,
+    pub breaks: Duration,
+    pub average: Option<Duration>,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+}
+
+impl Stats {
+    // Worked out from the task and break logs and the archive, so resetting the counters doesn't lose anything
+    pub fn new(todo: &ToDo, range: u32, today: NaiveDate) -> Self {
+        let start = today - Days::new(u64::from(range.max(1) - 1));
+        let days: Vec<NaiveDate> = start.iter_days().take_while(|day| *day <= today).collect();
+
+        let finished: Vec<_> = todo.archive.iter()
+            .filter(|task| task.status == TaskStatus::Completed)
+            .filter_map(|task| task.completed.map(|time| (time.date_naive(), task.time.export_time())))
+            .collect();
+        let completed_on = |day: NaiveDate| finished.iter().filter(|(date, _)| *date == day).count() as u32;
+        let completed: Vec<u32> = days.iter().map(|day| completed_on(*day)).collect();
+
+        let tracked = days.iter()
+            .map(|day| todo.all_tasks().chain(todo.archive.iter()).map(|task| task.log.on(*day)).sum())
+            .collect();
+        let breaks = todo.break_log.between(start, today);
+
+        let in_range: Vec<Duration> = finished.iter().filter(|(date, _)| *date >= start).map(|(_, time)| *time).collect();
+        let average = if in_range.is_empty() {None} else {Some(in_range.iter().sum::<Duration>() / in_range.len() as u32)};
+
+        // A day without a completed task yet doesn't break the streak until it is over
+        let mut day = if completed_on(today) == 0 {today.pred_opt()} else {Some(today)};
+        let mut current_streak = 0;
+        while let Some(date) = day && completed_on(date) > 0 {
+            current_streak += 1;
+            day = date.pred_opt();
+        }
+        let (_, longest_streak) = completed.iter().fold((0, 0), |(run, longest), count| {
+            let run = if *count > 0 {run + 1} else {0};
+            (run, longest.max(run))
+        });
+
+        Self { days, completed, tracked, breaks, average, current_streak, longest_streak }
+    }
+
+    // Share of the time tracked in the range that went on breaks
+    pub fn break_ratio(&self) -> Option<f32> {
+        let total = self.tracked.iter().sum::<Duration>() + self.breaks;
+        if total.is_zero() {None} else {Some(self.breaks.as_secs_f32() / total.as_secs_f32())}
+    }
+}
+
+// Bar chart with one bar per day
+#[derive(Debug)]
+struct BarChart {
+    values: Vec<f32>,
+    label: fn(f32) -> String,
+    color: Color,
+    first: NaiveDate,
+    last: NaiveDate,
+}
+
+impl<Message> canvas::Program<Message> for BarChart {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let label_color = theme.palette().text;
+        let (top, bottom) = (20.0, bounds.height - 20.0);
+        let max = self.values.iter().copied().fold(0.0, f32::max);
+
+        let axis = canvas::Path::line(Point::new(0.0, bottom), Point::new(bounds.width, bottom));
+        frame.stroke(&axis, canvas::Stroke::default().with_color(label_color).with_width(1.0));
+
+        if max > 0.0 {
+            let slot = bounds.width / self.values.len() as f32;
+            for (index, value) in self.values.iter().enumerate() {
+                let height = (bottom - top) * value / max;
+                frame.fill_rectangle(
+                    Point::new(index as f32 * slot + slot * 0.1, bottom - height),
+                    Size::new(slot * 0.8, height),
+                    self.color,
+                );
+            }
+        }
+
+        let label = |content: String, position: Point, horizontal_alignment| canvas::Text {
+            content,
+            position,
+            color: label_color,
+            size: 12.0.into(),
+            horizontal_alignment,
+            ..Default::default()
+        };
+        frame.fill_text(label(format!("Max {}", (self.label)(max)), Point::ORIGIN, iced::alignment::Horizontal::Left));
+        frame.fill_text(label(self.first.format("%d/%m").to_string(), Point::new(0.0, bottom + 4.0), iced::alignment::Horizontal::Left));
+        frame.fill_text(label(self.last.format("%d/%m").to_string(), Point::new(bounds.width, bottom + 4.0), iced::alignment::Horizontal::Right));
+
+        vec![frame.into_geometry()]
+    }
+}
+
+impl ToDo {
+    pub fn stats_view(&self) -> Element<'_, Message> {
+        let radius = 2;
+        let stats = Stats::new(self, self.stats_range, Local::now().date_naive());
+        let mut main: Column<'_, Message> = Column::new().width(Length::Fill).padding(20).spacing(10);
+
+        let mut ranges: Row<'_, Message> = Row::new()
+            .push(button("Back").on_press(Message::Show(Screen::Tasks)))
+            .push(text("Statistics").size(20))
+            .push(horizontal_space())
+            .spacing(10).align_y(Alignment::Center);
+        for range in RANGES {
+            let selected = range == self.stats_range;
+            ranges = ranges.push(
+                button(text(format!("{} days", range))).on_press_maybe(if selected {None} else {Some(Message::StatsRange(range))}).style(
+                    move |_: &Theme, _| {
+                        if selected {
+                            style_button(get_rgb_color(0, 155, 0), Color::WHITE, radius)
+                        } else {
+                            style_button(get_rgb_color(51, 89, 218), Color::WHITE, radius)
+                        }
+                    }
+                )
+            );
+        }
+        main = main.push(ranges);
+
+        main = main.push(
+            Row::new()
+                .push(text(format!("Completed: {}", stats.completed.iter().sum::<u32>())).size(16))
+                .push(text(format!("Average time per task: {}", stats.average.map(format_duration).unwrap_or_else(|| "-".to_owned()))).size(16))
+                .push(text(format!("Break ratio: {}", stats.break_ratio().map(|ratio| format!("{:.0}%", ratio * 100.0)).unwrap_or_else(|| "-".to_owned()))).size(16))
+                .spacing(30)
+        );
+        main = main.push(
+            Row::new()
+                .push(text(format!("Current streak: {} days", stats.current_streak)).size(16))
+                .push(text(format!("Longest streak: {} days", stats.longest_streak)).size(16))
+                .spacing(30)
+        );
+
+        let (first, last) = (stats.days[0], stats.days[stats.days.len() - 1]);
+        main = main.push(text("Tasks completed per day").size(18));
+        main = main.push(
+            canvas(BarChart {
+                values: stats.completed.iter().map(|count| *count as f32).collect(),
+                label: |value| format!("{:.0}", value),
+                color: get_rgb_color(0, 155, 0),
+                first,
+                last,
+            }).width(Length::Fill).height(Length::Fixed(180.0))
+        );
+        main = main.push(text("Time tracked per day").size(18));
+        main = main.push(
+            canvas(BarChart {
+                values: stats.tracked.iter().map(|time| time.as_secs_f32()).collect(),
+                label: |value| format_duration(Duration::from_secs_f32(value)),
+                color: get_rgb_color(51, 89, 218),
+                first,
+                last,
+            }).width(Length::Fill).height(Length::Fixed(180.0))
+        );
+
+        Scrollable::new(main).into()
+    }
+}
@@ -0,0 +1,74 @@
+use iced::{Alignment, Element, Length};
+use iced::widget::{button, checkbox, text_input, Column, Row};
+
+use crate::task::{Subtask, Task};
+use crate::{Message, ToDo};
+
+impl ToDo {
+    // Only one task's details are open at a time, so the inputs for subtasks and notes can be shared
+    pub fn toggle_expanded(&mut self, id: u64) {
+        self.expanded = if self.expanded == Some(id) {None} else {Some(id)};
+        self.new_subtask = String::new();
+        self.notes_editor = None;
+    }
+
+    pub fn add_subtask(&mut self) {
+        let title = self.new_subtask.trim().to_owned();
+        let Some(id) = self.expanded else {return};
+        if title.is_empty() || !self.tasks.iter().any(|task| task.id == id) {
+            return;
+        }
+        self.record();
+        if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
+            task.subtasks.push(Subtask { title, done: false });
+        }
+        self.new_subtask = String::new();
+    }
+
+    pub fn toggle_subtask(&mut self, id: u64, index: usize) {
+        if !self.tasks.iter().any(|task| task.id == id && index < task.subtasks.len()) {
+            return;
+        }
+        self.record();
+        if let Some(subtask) = self.tasks.iter_mut().find(|task| task.id == id).and_then(|task| task.subtasks.get_mut(index)) {
+            subtask.done = !subtask.done;
+        }
+    }
+
+    pub fn remove_subtask(&mut self, id: u64, index: usize) {
+        if !self.tasks.iter().any(|task| task.id == id && index < task.subtasks.len()) {
+            return;
+        }
+        self.record();
+        if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
+            task.subtasks.remove(index);
+        }
+    }
+
+    // With the setting on, open subtasks hold the parent back, otherwise completing it ticks them off
+    pub fn can_complete(&self, task: &Task) -> bool {
+        !self.config.subtasks_block_completion || task.subtasks.iter().all(|subtask| subtask.done)
+    }
+
+    pub fn subtasks_view<'a>(&'a self, task: &'a Task) -> Element<'a, Message> {
+        let id = task.id;
+        let mut list: Column<'_, Message> = Column::new().spacing(5).padding([0, 40]);
+        for (index, subtask) in task.subtasks.iter().enumerate() {
+            list = list.push(
+                Row::new()
+                    .push(checkbox(&subtask.title, subtask.done).on_toggle(move |_| Message::ToggleSubtask(id, index)))
+                    .push(button("Remove").on_press(Message::RemoveSubtask(id, index)))
+                    .spacing(10).align_y(Alignment::Center)
+            );
+        }
+        list.push(
+            Row::new()
+                .push(text_input("New Subtask ...", &self.new_subtask)
+                    .on_input(Message::SubtaskText)
+                    .on_submit(Message::AddSubtask)
+                    .width(Length::Fixed(240.0)))
+                .push(button("Add").on_press_maybe(if self.new_subtask.trim().is_empty() {None} else {Some(Message::AddSubtask)}))
+                .spacing(10).align_y(Alignment::Center)
+        ).into()
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt;
+
+use chrono::prelude::{DateTime, Local};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::recur::Recurrence;
+use crate::time::{DayLog, Time};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    Active,
+    Completed,
+    Removed,
+}
+
+// Ordered from lowest to highest so sorting can compare them directly
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    pub const ALL: [Priority; 5] = [Priority::None, Priority::Low, Priority::Medium, Priority::High, Priority::Urgent];
+
+    // Badge colour, tasks without a priority don't get a badge
+    pub fn color(&self) -> Option<(u8, u8, u8)> {
+        match self {
+            Priority::None => None,
+            Priority::Low => Some((120, 120, 120)),
+            Priority::Medium => Some((51, 89, 218)),
+            Priority::High => Some((255, 140, 0)),
+            Priority::Urgent => Some((220, 8, 51)),
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Priority::None => "No Priority",
+            Priority::Low => "Low",
+            Priority::Medium => "Medium",
+            Priority::High => "High",
+            Priority::Urgent => "Urgent",
+        })
+    }
+}
+
+// When a task should be done by, a date on its own is due from the start of that day
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct Due {
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub time: Option<NaiveTime>,
+}
+
+impl Due {
+    // Takes dd/mm/yyyy with an optional HH:MM, the same day first order as the clock
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Ok(moment) = NaiveDateTime::parse_from_str(value, "%d/%m/%Y %H:%M") {
+            return Some(Self { date: moment.date(), time: Some(moment.time()) });
+        }
+        NaiveDate::parse_from_str(value, "%d/%m/%Y").ok().map(|date| Self { date, time: None })
+    }
+
+    // The moment the reminder goes off
+    pub fn moment(&self) -> NaiveDateTime {
+        self.date.and_time(self.time.unwrap_or(NaiveTime::MIN))
+    }
+
+    // Date-only tasks only count as overdue once their day is over
+    pub fn is_overdue(&self, now: NaiveDateTime) -> bool {
+        match self.time {
+            Some(_) => self.moment() < now,
+            None => self.date < now.date(),
+        }
+    }
+
+    pub fn is_today(&self, now: NaiveDateTime) -> bool {
+        self.date == now.date()
+    }
+}
+
+impl fmt::Display for Due {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.time {
+            Some(time) => write!(f, "{} {}", self.date.format("%d/%m/%Y"), time.format("%H:%M")),
+            None => write!(f, "{}", self.date.format("%d/%m/%Y")),
+        }
+    }
+}
+
+// One item of a task's checklist
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Subtask {
+    pub title: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Task {
+    pub id: u64,
+    pub title: String,
+    pub notes: String,
+    pub created: DateTime<Local>,
+    pub completed: Option<DateTime<Local>>,
+    pub status: TaskStatus,
+    pub priority: Priority,
+    pub due: Option<Due>,
+    // Completing a recurring task adds its next instance
+    pub recurrence: Option<Recurrence>,
+    pub subtasks: Vec<Subtask>,
+    pub tags: Vec<String>,
+    // Name of the list an archived task came from
+    pub list: Option<String>,
+    pub time: Time,
+    // Finished pomodoro work phases spent on this task
+    pub pomodoros: u32,
+    // The task's time split up by the day it was spent on
+    pub log: DayLog,
+}
+
+impl Task {
+    pub fn new(id: u64, title: String) -> Self {
+        Self {
+            id,
+            title,
+            notes: String::new(),
+            created: Local::now(),
+            completed: None,
+            status: TaskStatus::Active,
+            priority: Priority::None,
+            due: None,
+            recurrence: None,
+            subtasks: Vec::new(),
+            tags: Vec::new(),
+            list: None,
+            time: Time::default(),
+            pomodoros: 0,
+            log: DayLog::default(),
+        }
+    }
+
+    // Marks the task as finished, either completed or removed
+    pub fn finish(&mut self, completed: bool) {
+        self.completed = Some(Local::now());
+        self.status = if completed {TaskStatus::Completed} else {TaskStatus::Removed};
+    }
+}
@@ -0,0 +1,6 @@
+// Each file tests one module
+mod save;
+mod error;
+mod alerts;
+mod recur;
+mod fs;
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::os::unix::net::UnixListener;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{Local, TimeDelta, Timelike};
+use iced::futures::StreamExt;
+use iced_runtime::Action;
+use zbus::zvariant::OwnedValue;
+
+use crate::task::{Due, Task};
+use crate::time::Time;
+use crate::{Message, ToDo};
+
+// Summary and body of every Notify call the mock service received
+type Calls = Arc<Mutex<Vec<(String, String)>>>;
+
+struct MockNotifications {
+    calls: Calls,
+}
+
+#[zbus::interface(name = "org.freedesktop.Notifications")]
+impl MockNotifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(&self, _app_name: String, _replaces_id: u32, _app_icon: String, summary: String, body: String,
+        _actions: Vec<String>, _hints: HashMap<String, OwnedValue>, _expire_timeout: i32) -> u32 {
+        let mut calls = self.calls.lock().unwrap();
+        calls.push((summary, body));
+        calls.len() as u32
+    }
+}
+
+// Just enough of the bus itself for a session connection to sign on
+struct MockBus;
+
+#[zbus::interface(name = "org.freedesktop.DBus")]
+impl MockBus {
+    fn hello(&self) -> String {
+        ":1.1".to_owned()
+    }
+}
+
+// Serves the notification service on a private socket and points the session bus at it
+fn mock_session_bus() -> Calls {
+    let calls = Calls::default();
+    let path = std::env::temp_dir().join(format!("todo-list-bus-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    let served = calls.clone();
+    std::thread::spawn(move || {
+        let mut connections = Vec::new();
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {break};
+            let connection = zbus::blocking::connection::Builder::unix_stream(stream)
+                .server(zbus::Guid::generate()).unwrap()
+                .p2p()
+                .serve_at("/org/freedesktop/DBus", MockBus).unwrap()
+                .serve_at("/org/freedesktop/Notifications", MockNotifications { calls: served.clone() }).unwrap()
+                .build();
+            connections.extend(connection.ok());
+        }
+    });
+    // Only this test talks to the session bus
+    unsafe { std::env::set_var("DBUS_SESSION_BUS_ADDRESS", format!("unix:path={}", path.display())) };
+    calls
+}
+
+// Runs a task returned by update the way the iced runtime would, keeping its messages
+fn run(task: iced::Task<Message>) -> Vec<Message> {
+    let Some(stream) = iced_runtime::task::into_stream(task) else {return Vec::new()};
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    runtime.block_on(stream.filter_map(|action| async move {
+        match action {
+            Action::Output(message) => Some(message),
+            _ => None,
+        }
+    }).collect())
+}
+
+// A task whose current stretch has been running for the given time
+fn todo_with_task(stretch: Duration) -> ToDo {
+    let mut todo = ToDo::default();
+    let mut task = Task::new(0, "Write report".to_owned());
+    task.time = Time::new(Instant::now().checked_sub(stretch).expect("clock should reach back that far"));
+    task.time.tick();
+    todo.tasks.push(task);
+    todo.active = Some(0);
+    todo
+}
+
+#[test]
+fn long_task_fires_once() {
+    let mut todo = todo_with_task(Duration::from_secs(61));
+    todo.config.task_alert_minutes = 1;
+    let alerts = todo.crossed_thresholds();
+    assert_eq!(alerts, vec![("Long task", "\"Write report\" has been running for over 00:01:00".to_owned())]);
+    assert!(todo.crossed_thresholds().is_empty());
+}
+
+#[test]
+fn thresholds_below_limit_or_off_stay_quiet() {
+    let mut todo = todo_with_task(Duration::from_secs(59));
+    todo.config.task_alert_minutes = 1;
+    assert!(todo.crossed_thresholds().is_empty());
+
+    let mut todo = todo_with_task(Duration::from_secs(120));
+    todo.config.task_alert_minutes = 0;
+    assert!(todo.crossed_thresholds().is_empty());
+}
+
+#[test]
+fn time_from_earlier_sessions_does_not_count() {
+    let mut todo = todo_with_task(Duration::ZERO);
+    todo.config.task_alert_minutes = 1;
+    todo.tasks[0].time.add(Duration::from_secs(2 * 3600));
+    assert!(todo.crossed_thresholds().is_empty());
+}
+
+#[test]
+fn long_task_fires_again_for_a_new_stretch() {
+    let mut todo = todo_with_task(Duration::from_secs(61));
+    todo.config.task_alert_minutes = 1;
+    assert_eq!(todo.crossed_thresholds().len(), 1);
+    todo.tasks[0].time.new_start();
+    assert!(todo.crossed_thresholds().is_empty());
+    todo.tasks[0].time = Time::new(Instant::now().checked_sub(Duration::from_secs(61)).unwrap());
+    todo.tasks[0].time.tick();
+    assert_eq!(todo.crossed_thresholds().len(), 1);
+}
+
+#[test]
+fn reset_arms_the_alerts_again() {
+    let mut todo = todo_with_task(Duration::from_secs(61));
+    todo.config.task_alert_minutes = 1;
+    assert_eq!(todo.crossed_thresholds().len(), 1);
+    let _ = todo.update(Message::Reset(true));
+    let _ = todo.update(Message::Reset(true));
+    assert!(!todo.fired.long_task);
+}
+
+#[test]
+fn long_break_fires_once_per_break() {
+    let mut todo = ToDo::default();
+    todo.config.break_alert_minutes = 1;
+    todo.rest = true;
+    todo.break_time = Time::new(Instant::now().checked_sub(Duration::from_secs(90)).expect("clock should reach back 90 seconds"));
+    todo.break_time.tick();
+    assert_eq!(todo.crossed_thresholds().len(), 1);
+    assert!(todo.crossed_thresholds().is_empty());
+
+    // Ending the break arms the alert again
+    todo.rest = false;
+    assert!(todo.crossed_thresholds().is_empty());
+    todo.rest = true;
+    assert_eq!(todo.crossed_thresholds().len(), 1);
+}
+
+#[test]
+fn due_reminder_fires_only_when_its_time_passes() {
+    let now = Local::now();
+    let moment = now.naive_local().with_second(0).unwrap().with_nanosecond(0).unwrap();
+    let mut todo = todo_with_task(Duration::ZERO);
+    todo.config.task_alert_minutes = 0;
+    todo.time = now;
+    todo.tasks[0].due = Some(Due { date: moment.date(), time: Some(moment.time()) });
+
+    // Already past on the first check, as after a launch or a load
+    assert!(todo.crossed_thresholds().is_empty());
+
+    todo.fired.due_checked = Some(moment - TimeDelta::seconds(1));
+    let alerts = todo.crossed_thresholds();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].0, "Task due");
+    assert!(todo.crossed_thresholds().is_empty());
+}
+
+// A crossed threshold reaches the notification service on the session bus exactly once,
+// and not at all with desktop notifications turned off
+#[test]
+fn crossed_threshold_sends_one_notification() {
+    let calls = mock_session_bus();
+
+    let mut todo = todo_with_task(Duration::from_secs(120));
+    todo.config.task_alert_minutes = 1;
+    todo.config.desktop_notifications = true;
+    let mut messages = Vec::new();
+    for _ in 0..3 {
+        messages.extend(run(todo.check_alerts()));
+    }
+    assert!(matches!(messages.as_slice(), [Message::Notified(Ok(()))]), "{:?}", messages);
+    assert_eq!(todo.alerts, vec!["Long task: \"Write report\" has been running for over 00:01:00".to_owned()]);
+    assert_eq!(*calls.lock().unwrap(), vec![("Long task".to_owned(), "\"Write report\" has been running for over 00:01:00".to_owned())]);
+
+    let mut quiet = todo_with_task(Duration::from_secs(120));
+    quiet.config.task_alert_minutes = 1;
+    quiet.config.desktop_notifications = false;
+    assert!(run(quiet.check_alerts()).is_empty());
+    assert_eq!(quiet.alerts.len(), 1);
+    assert_eq!(calls.lock().unwrap().len(), 1);
+}
@@ -0,0 +1,64 @@
+use std::io;
+
+use serde_json::json;
+
+use crate::error::LoadError;
+use crate::fs::get_todo;
+use crate::save::SaveFile;
+
+fn load_error(contents: &str) -> LoadError {
+    get_todo(contents).expect_err("contents should fail to load")
+}
+
+#[test]
+fn reports_position_of_bad_json() {
+    let error = load_error("{\n    \"tasks\": [,\n}");
+    let LoadError::Parse { line, column, message } = error else {panic!("expected a parse error, got {:?}", error)};
+    assert_eq!((line, column), (2, 15));
+    assert_eq!(message, "expected value");
+}
+
+#[test]
+fn reports_missing_field() {
+    let error = load_error(r#"{"tasks": [], "break_time": [0, 0], "prev_task": [0, 0]}"#);
+    assert_eq!(error, LoadError::MissingField("cur_task".to_owned()));
+}
+
+#[test]
+fn reports_missing_field_with_its_path() {
+    let error = SaveFile::from_value(json!({
+        "version": 1,
+        "completed": 0,
+        "removed": 0,
+        "tasks": [{"id": 0, "title": "No created date", "status": "active"}],
+        "break_time": [0, 0],
+        "prev_task": [0, 0],
+    })).unwrap_err();
+    assert_eq!(error, LoadError::MissingField("tasks[0].created".to_owned()));
+}
+
+#[test]
+fn reports_wrong_type() {
+    let error = load_error(r#"{"tasks": [], "break_time": "ten minutes", "cur_task": [0, 0], "prev_task": [0, 0]}"#);
+    let LoadError::WrongType { field, message } = error else {panic!("expected a type error, got {:?}", error)};
+    assert_eq!(field, "break_time");
+    assert!(message.starts_with("invalid type: string \"ten minutes\""), "{}", message);
+}
+
+#[test]
+fn reports_wrong_version_type() {
+    let error = load_error(r#"{"version": "two"}"#);
+    assert!(matches!(error, LoadError::WrongType { ref field, .. } if field == "version"), "{:?}", error);
+}
+
+#[test]
+fn reports_unsupported_version() {
+    let error = load_error(r#"{"version": 99, "lists": [], "break_time": [0, 0], "prev_task": [0, 0]}"#);
+    assert_eq!(error, LoadError::UnsupportedVersion(99));
+    assert_eq!(error.to_string(), "Save file version 99 is newer than this app supports");
+}
+
+#[test]
+fn keeps_io_error_kind() {
+    assert_eq!(LoadError::from(io::Error::from(io::ErrorKind::NotFound)), LoadError::Io(io::ErrorKind::NotFound));
+}
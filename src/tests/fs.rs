@@ -0,0 +1,66 @@
+use std::path::{Path, PathBuf};
+
+use crate::fs::{is_backup_suffix, list_backups, save_to_file, Backups};
+use crate::save::SaveFile;
+use crate::ToDo;
+
+// A fresh directory under the system temp dir, removed again when dropped
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("todo-list-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(std::fs::canonicalize(dir).unwrap())
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn save(path: &Path, backups: &Backups) {
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    runtime.block_on(save_to_file(path.to_owned(), SaveFile::from(&ToDo::default()), backups.clone())).unwrap();
+}
+
+#[test]
+fn matches_backup_suffixes() {
+    assert!(is_backup_suffix("20260118-093000.json"));
+    assert!(is_backup_suffix("20260118-093000-2.json"));
+    assert!(is_backup_suffix("20260118-093000-12.json"));
+    for suffix in ["20260118-093000", "20260118-093000-.json", "20260118-093000-x.json", "2026011-093000.json",
+        "20260118_093000.json", "2.json", "20260118-093000-2-3.json", ""] {
+        assert!(!is_backup_suffix(suffix), "{}", suffix);
+    }
+}
+
+// Four saves over the same file in quick succession keep the two newest copies,
+// and a save of the same name in another directory keeps its own
+#[test]
+fn prunes_each_source_separately() {
+    let root = TempDir::new("backups");
+    let backups = Backups { dir: root.0.join("backups"), keep: 2 };
+    let first = root.0.join("a").join("work.json");
+    let second = root.0.join("b").join("work.json");
+    // Looks like a backup of "work" by name, but belongs to another save
+    let other = root.0.join("a").join("work-2.json");
+    for _ in 0..4 {
+        save(&first, &backups);
+        save(&second, &backups);
+        save(&other, &backups);
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let listed = runtime.block_on(list_backups(backups.dir.clone())).unwrap();
+    assert_eq!(listed.len(), 6);
+    for source in [&first, &second, &other] {
+        let copies: Vec<_> = listed.iter().filter(|backup| backup.source.as_ref() == Some(source)).collect();
+        assert_eq!(copies.len(), 2, "{}", source.display());
+        let folder = copies[0].path.parent().unwrap();
+        assert!(copies.iter().all(|backup| backup.path.parent() == Some(folder)));
+    }
+}
@@ -0,0 +1,94 @@
+use chrono::{Local, NaiveDate, TimeZone, Weekday};
+use serde_json::json;
+
+use crate::recur::Recurrence;
+use crate::task::{Due, Task};
+use crate::ToDo;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn parses_every_rule() {
+    assert_eq!(Recurrence::parse(" Daily "), Some(Recurrence::Daily));
+    assert_eq!(Recurrence::parse("weekdays"), Some(Recurrence::Weekdays));
+    assert_eq!(Recurrence::parse("weekly mon,thu"), Some(Recurrence::Weekly { days: vec![Weekday::Mon, Weekday::Thu] }));
+    assert_eq!(Recurrence::parse("weekly mon, thu"), Some(Recurrence::Weekly { days: vec![Weekday::Mon, Weekday::Thu] }));
+    assert_eq!(Recurrence::parse("monthly"), Some(Recurrence::Monthly { day: None }));
+    assert_eq!(Recurrence::parse("monthly on 31"), Some(Recurrence::Monthly { day: Some(31) }));
+    assert_eq!(Recurrence::parse("every 3 days"), Some(Recurrence::Days { count: 3 }));
+    assert_eq!(Recurrence::parse("every 1 day"), Some(Recurrence::Days { count: 1 }));
+}
+
+#[test]
+fn rejects_bad_rules() {
+    for rule in ["", "every 0 days", "every -1 days", "weekly", "weekly funday", "monthly on 0", "monthly on 32", "yearly"] {
+        assert_eq!(Recurrence::parse(rule), None, "{}", rule);
+    }
+}
+
+#[test]
+fn display_parses_back() {
+    for rule in [Recurrence::Daily, Recurrence::Weekdays, Recurrence::Weekly { days: vec![Weekday::Tue, Weekday::Sat] },
+        Recurrence::Monthly { day: None }, Recurrence::Monthly { day: Some(15) }, Recurrence::Days { count: 10 }] {
+        assert_eq!(Recurrence::parse(&rule.to_string()), Some(rule));
+    }
+}
+
+#[test]
+fn weekdays_skip_the_weekend() {
+    // 2026-01-09 is a Friday
+    assert_eq!(Recurrence::Weekdays.next_after(date(2026, 1, 9)), date(2026, 1, 12));
+    assert_eq!(Recurrence::Weekdays.next_after(date(2026, 1, 10)), date(2026, 1, 12));
+    assert_eq!(Recurrence::Weekdays.next_after(date(2026, 1, 12)), date(2026, 1, 13));
+}
+
+#[test]
+fn weekly_goes_to_the_next_listed_day() {
+    let rule = Recurrence::Weekly { days: vec![Weekday::Mon, Weekday::Thu] };
+    // Monday to Thursday, then on to the next Monday
+    assert_eq!(rule.next_after(date(2026, 1, 12)), date(2026, 1, 15));
+    assert_eq!(rule.next_after(date(2026, 1, 15)), date(2026, 1, 19));
+}
+
+#[test]
+fn monthly_clamps_without_drifting() {
+    let rule = Recurrence::Monthly { day: Some(31) };
+    let february = rule.next_after(date(2026, 1, 31));
+    assert_eq!(february, date(2026, 2, 28));
+    let march = rule.next_after(february);
+    assert_eq!(march, date(2026, 3, 31));
+    assert_eq!(rule.next_after(march), date(2026, 4, 30));
+    assert_eq!(rule.next_after(date(2028, 1, 31)), date(2028, 2, 29));
+    assert_eq!(rule.next_after(date(2026, 12, 31)), date(2027, 1, 31));
+    // Without an anchor the day given is kept
+    assert_eq!(Recurrence::Monthly { day: None }.next_after(date(2026, 1, 15)), date(2026, 2, 15));
+}
+
+#[test]
+fn every_few_days() {
+    assert_eq!(Recurrence::Days { count: 3 }.next_after(date(2026, 2, 27)), date(2026, 3, 2));
+}
+
+#[test]
+fn reads_monthly_saved_without_a_day() {
+    let rule: Recurrence = serde_json::from_value(json!({"every": "monthly"})).unwrap();
+    assert_eq!(rule, Recurrence::Monthly { day: None });
+    assert_eq!(serde_json::to_value(Recurrence::Monthly { day: Some(31) }).unwrap(), json!({"every": "monthly", "day": 31}));
+}
+
+#[test]
+fn next_instances_keep_the_month_end() {
+    let mut todo = ToDo { time: Local.with_ymd_and_hms(2026, 1, 31, 12, 0, 0).unwrap(), next_id: 1, ..Default::default() };
+    let mut task = Task::new(0, "Rent".to_owned());
+    task.due = Some(Due { date: date(2026, 1, 31), time: None });
+    task.recurrence = Recurrence::parse("monthly");
+
+    let february = todo.next_instance(&task).unwrap();
+    assert_eq!(february.due.map(|due| due.date), Some(date(2026, 2, 28)));
+    assert_eq!(february.recurrence, Some(Recurrence::Monthly { day: Some(31) }));
+    todo.time = Local.with_ymd_and_hms(2026, 2, 28, 12, 0, 0).unwrap();
+    let march = todo.next_instance(&february).unwrap();
+    assert_eq!(march.due.map(|due| due.date), Some(date(2026, 3, 31)));
+}
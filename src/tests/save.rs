@@ -0,0 +1,259 @@
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate, TimeDelta, Weekday};
+use serde_json::{json, Value};
+
+use crate::fs::get_todo;
+use crate::lists::{TaskList, DEFAULT_LIST};
+use crate::recur::Recurrence;
+use crate::resume::{Activity, Gap};
+use crate::save::{ClockState, SaveFile, SAVE_VERSION};
+use crate::task::{Due, Priority, Subtask, Task, TaskStatus};
+use crate::time::Time;
+use crate::ToDo;
+
+fn load(value: Value) -> SaveFile {
+    SaveFile::from_value(value).expect("save should load")
+}
+
+fn titles(tasks: &[Task]) -> Vec<&str> {
+    tasks.iter().map(|task| task.title.as_str()).collect()
+}
+
+fn record(id: u64, title: &str) -> Value {
+    json!({
+        "id": id,
+        "title": title,
+        "created": "2024-03-01T09:00:00+00:00",
+        "status": "active",
+        "time": [90, 500],
+    })
+}
+
+// The exact layout the first release wrote
+#[test]
+fn loads_baseline_layout() {
+    let save = load(json!({
+        "completed": 3,
+        "removed": 1,
+        "tasks": ["Write report", "Email team"],
+        "break_time": [10, 500],
+        "cur_task": [65, 250],
+        "prev_task": [7, 0],
+    }));
+    assert_eq!(save.version, SAVE_VERSION);
+    assert_eq!(save.lists.len(), 1);
+    let list = &save.lists[0];
+    assert_eq!(list.name, DEFAULT_LIST);
+    assert_eq!((list.completed, list.removed), (3, 1));
+    assert_eq!(list.tasks.iter().map(|task| (task.id, task.title.as_str())).collect::<Vec<_>>(), vec![(0, "Write report"), (1, "Email team")]);
+    // The single running timer goes to the first task, which becomes the active one
+    assert_eq!(list.tasks[0].time, Duration::from_millis(65_250));
+    assert_eq!(list.tasks[1].time, Duration::ZERO);
+    assert_eq!(save.active, Some(0));
+    assert_eq!(save.break_time, Duration::from_millis(10_500));
+    assert_eq!(save.prev_task, Duration::from_secs(7));
+}
+
+#[test]
+fn loads_baseline_file_into_todo() {
+    let todo = get_todo(r#"{
+        "completed": 2,
+        "removed": 0,
+        "tasks": ["One", "Two"],
+        "break_time": [0, 0],
+        "cur_task": [3, 0],
+        "prev_task": [0, 0]
+    }"#).expect("baseline file should load");
+    assert_eq!(titles(&todo.tasks), vec!["One", "Two"]);
+    assert_eq!(todo.complete, 2);
+    assert_eq!(todo.active, Some(0));
+    assert_eq!(todo.tasks[0].time.export_time(), Duration::from_secs(3));
+    assert_eq!(todo.next_id, 2);
+}
+
+#[test]
+fn loads_legacy_objects_without_time() {
+    let save = load(json!({
+        "tasks": [
+            {"id": 4, "title": "Kept id", "status": "active"},
+            {"title": "Fallback id", "notes": "Some notes"},
+        ],
+        "archive": [{"title": "Archived", "status": "completed"}],
+        "break_time": [0, 0],
+        "cur_task": [42, 0],
+        "prev_task": [0, 0],
+    }));
+    let tasks = &save.lists[0].tasks;
+    assert_eq!(tasks.iter().map(|task| task.id).collect::<Vec<_>>(), vec![4, 5]);
+    assert_eq!(tasks[1].notes, "Some notes");
+    assert_eq!(tasks[0].time, Duration::from_secs(42));
+    assert_eq!(save.active, Some(4));
+    // Missing ids follow the highest one given, open tasks first
+    assert_eq!(save.archive[0].id, 6);
+    assert_eq!(save.archive[0].status, TaskStatus::Completed);
+}
+
+#[test]
+fn fallback_ids_skip_explicit_ones() {
+    let save = load(json!({
+        "tasks": [{"title": "A"}, {"id": 1, "title": "B"}, {"title": "C"}],
+        "archive": [{"id": 0, "title": "D"}, {"title": "E"}],
+        "break_time": [0, 0],
+        "cur_task": [0, 0],
+        "prev_task": [0, 0],
+    }));
+    assert_eq!(save.lists[0].tasks.iter().map(|task| task.id).collect::<Vec<_>>(), vec![2, 1, 3]);
+    assert_eq!(save.archive.iter().map(|task| task.id).collect::<Vec<_>>(), vec![0, 4]);
+}
+
+#[test]
+fn loads_legacy_objects_with_time() {
+    let save = load(json!({
+        "completed": 1,
+        "tasks": [
+            {"id": 0, "title": "Timed", "time": [30, 0]},
+            {"id": 1, "title": "Untimed"},
+        ],
+        "break_time": [0, 0],
+        "cur_task": [99, 0],
+        "prev_task": [0, 0],
+    }));
+    let tasks = &save.lists[0].tasks;
+    // Per-task times are kept, the old running timer isn't handed out again
+    assert_eq!(tasks[0].time, Duration::from_secs(30));
+    assert_eq!(tasks[1].time, Duration::ZERO);
+    assert_eq!(save.active, None);
+}
+
+#[test]
+fn reads_durations_as_seconds_and_milliseconds() {
+    let save = load(json!({
+        "tasks": [],
+        "break_time": [1, 999],
+        "cur_task": [0, 0],
+        "prev_task": [2, 1],
+    }));
+    assert_eq!(save.break_time, Duration::from_millis(1_999));
+    assert_eq!(save.prev_task, Duration::from_millis(2_001));
+}
+
+#[test]
+fn migrates_version_one_into_default_list() {
+    let save = load(json!({
+        "version": 1,
+        "completed": 5,
+        "removed": 2,
+        "tasks": [record(3, "First"), record(8, "Second")],
+        "archive": [record(1, "Old")],
+        "active": 8,
+        "break_time": [4, 0],
+        "prev_task": [6, 0],
+        "break_log": {"2024-03-01": 120},
+    }));
+    assert_eq!(save.version, SAVE_VERSION);
+    assert_eq!(save.lists.len(), 1);
+    assert_eq!(save.current_list, 0);
+    let list = &save.lists[0];
+    assert_eq!(list.name, DEFAULT_LIST);
+    assert_eq!((list.completed, list.removed), (5, 2));
+    assert_eq!(list.tasks.iter().map(|task| task.id).collect::<Vec<_>>(), vec![3, 8]);
+    assert_eq!(list.tasks[0].time, Duration::from_millis(90_500));
+    assert_eq!(save.archive[0].title, "Old");
+    assert_eq!(save.active, Some(8));
+    assert_eq!(save.break_log.get(&NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()), Some(&Duration::from_secs(120)));
+
+    let todo = save.into_todo();
+    assert_eq!(todo.next_id, 9);
+    assert_eq!(todo.break_time.export_time(), Duration::from_secs(4));
+}
+
+#[test]
+fn rejects_non_object() {
+    assert!(SaveFile::from_value(json!(["not", "a", "save"])).is_err());
+}
+
+// Everything a task can carry, spread over two lists, survives a save and load
+#[test]
+fn round_trips_current_version() {
+    let mut first = Task::new(0, "Plan".to_owned());
+    first.notes = "# Heading\n- item".to_owned();
+    first.priority = Priority::High;
+    first.due = Some(Due { date: NaiveDate::from_ymd_opt(2024, 5, 6).unwrap(), time: None });
+    first.recurrence = Some(Recurrence::Weekly { days: vec![Weekday::Mon, Weekday::Thu] });
+    first.subtasks = vec![Subtask { title: "Step".to_owned(), done: true }];
+    first.tags = vec!["work".to_owned()];
+    first.time = Time::from(Duration::from_millis(12_345));
+    first.pomodoros = 2;
+    let mut other = Task::new(5, "Shop".to_owned());
+    other.recurrence = Some(Recurrence::Days { count: 3 });
+    let mut archived = Task::new(2, "Done".to_owned());
+    archived.finish(true);
+    archived.list = Some("Home".to_owned());
+
+    let mut todo = ToDo::from(vec![first], vec![archived], Some(0), 4, 1, Duration::from_secs(8), Duration::from_secs(9));
+    todo.lists = vec![
+        TaskList::new(DEFAULT_LIST.to_owned()),
+        TaskList { name: "Home".to_owned(), tasks: vec![other], complete: 7, removed: 3 },
+    ];
+
+    let value = serde_json::to_value(SaveFile::from(&todo)).unwrap();
+    assert_eq!(value["version"], json!(SAVE_VERSION));
+    let loaded = load(value).into_todo();
+
+    assert_eq!(loaded.lists.iter().map(|list| list.name.as_str()).collect::<Vec<_>>(), vec![DEFAULT_LIST, "Home"]);
+    assert_eq!(loaded.current_list, 0);
+    assert_eq!((loaded.complete, loaded.removed), (4, 1));
+    assert_eq!((loaded.lists[1].complete, loaded.lists[1].removed), (7, 3));
+    assert_eq!(loaded.active, Some(0));
+    assert_eq!(loaded.next_id, 6);
+    assert_eq!(loaded.last_task.export_time(), Duration::from_secs(8));
+    assert_eq!(loaded.break_time.export_time(), Duration::from_secs(9));
+
+    let task = &loaded.tasks[0];
+    let original = &todo.tasks[0];
+    assert_eq!(task.title, original.title);
+    assert_eq!(task.notes, original.notes);
+    assert_eq!(task.created, original.created);
+    assert_eq!(task.priority, original.priority);
+    assert_eq!(task.due, original.due);
+    assert_eq!(task.recurrence, original.recurrence);
+    assert_eq!(task.subtasks, original.subtasks);
+    assert_eq!(task.tags, original.tags);
+    assert_eq!(task.pomodoros, 2);
+    assert_eq!(task.time.export_time(), Duration::from_millis(12_345));
+
+    assert_eq!(titles(&loaded.lists[1].tasks), vec!["Shop"]);
+    assert_eq!(loaded.lists[1].tasks[0].recurrence, Some(Recurrence::Days { count: 3 }));
+    assert_eq!(titles(&loaded.archive), vec!["Done"]);
+    assert_eq!(loaded.archive[0].status, TaskStatus::Completed);
+    assert_eq!(loaded.archive[0].list.as_deref(), Some("Home"));
+}
+
+// Saving mid-add, as auto-save can, leaves the half typed task out
+#[test]
+fn skips_task_being_added() {
+    let mut todo = ToDo::default();
+    todo.tasks.push(Task::new(0, "Kept".to_owned()));
+    todo.tasks.push(Task::new(1, "half typ".to_owned()));
+    todo.add = true;
+    let save = SaveFile::from(&todo);
+    assert_eq!(save.lists[0].tasks.iter().map(|task| task.title.as_str()).collect::<Vec<_>>(), vec!["Kept"]);
+}
+
+// A stretch stamped after the save time, as after a clock change, only earns the time since it started
+#[test]
+fn gap_counts_from_the_later_of_save_and_start() {
+    let todo = ToDo::from(vec![Task::new(0, "Plan".to_owned())], Vec::new(), Some(0), 0, 0, Duration::ZERO, Duration::ZERO);
+    let mut save = SaveFile::from(&todo);
+    let now = Local::now();
+    save.clock = Some(ClockState { saved_at: now - TimeDelta::hours(2), rest: false, sleep: false, started_at: Some(now - TimeDelta::minutes(30)) });
+    let Some(Gap { activity, duration }) = save.into_todo().gap else {panic!("gap should be found")};
+    assert_eq!(activity, Activity::Task(0));
+    assert!(duration >= Duration::from_secs(30 * 60) && duration < Duration::from_secs(31 * 60), "{:?}", duration);
+
+    // Nothing was running, so there is nothing to credit
+    let mut save = SaveFile::from(&todo);
+    save.clock = Some(ClockState { saved_at: now - TimeDelta::hours(2), rest: true, sleep: true, started_at: None });
+    assert_eq!(save.into_todo().gap, None);
+}
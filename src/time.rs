@@ -1,4 +1,8 @@
-use std::{default, time::{Duration, Instant}};
+use chrono::prelude::{DateTime, Local};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant};
 
 
 #[derive(Debug, PartialEq, Clone)]
@@ -21,7 +25,7 @@ impl Default for Time {
 impl Time {
     pub fn new(start: Instant) -> Self{
         Self {
-            start: start,
+            start,
             .. Default::default()
         }
     }
@@ -40,7 +44,7 @@ impl Time {
 
     // Moves the current time to append, resets current time to 0
     pub fn swap_current(&mut self) {
-        self.append = self.append + self.time_from;
+        self.append += self.time_from;
         self.new_start();
     }
 
@@ -50,23 +54,94 @@ impl Time {
         self.tick();
     }
 
-    pub fn to_string(&self) -> String {
-        format_duration(self.time_from + self.append)
-    }
-
     pub fn copy(&mut self, source:&mut Self) {
         source.swap_current();
         self.append = source.append;
         self.new_start();
     }
 
+    // Part of the current stretch counted after the given moment
+    pub fn current_since(&self, moment: Instant) -> Duration {
+        self.time_from.saturating_sub(moment.saturating_duration_since(self.start))
+    }
+
+    // When the current stretch started
+    pub fn stretch_start(&self) -> Instant {
+        self.start
+    }
+
     pub fn export_time(&self) -> Duration {
         self.append + self.time_from
     }
 
+    // Length of the current stretch, without time carried over from before
+    pub fn current(&self) -> Duration {
+        self.time_from
+    }
+
+    // Wall clock time the current stretch was started at
+    pub fn started_at(&self) -> DateTime<Local> {
+        Local::now() - chrono::Duration::from_std(self.start.elapsed()).unwrap_or_default()
+    }
+
+    // Adds time that passed outside of the timer, like while the app was closed
+    pub fn add(&mut self, time: Duration) {
+        self.append += time;
+    }
+
+    // Takes back time that was counted but shouldn't have been, call swap_current first
+    pub fn subtract(&mut self, time: Duration) {
+        self.append = self.append.saturating_sub(time);
+    }
+
+}
+
+// Time booked against each calendar day, kept in step with a timer's running total
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DayLog {
+    pub days: BTreeMap<NaiveDate, Duration>,
+    // Timer total at the last sync, anything above it is new time
+    logged: Duration,
+}
+
+impl DayLog {
+    pub fn from_days(days: BTreeMap<NaiveDate, Duration>, total: Duration) -> Self {
+        Self { days, logged: total }
+    }
+
+    // Books the change in the timer's total since the last sync against the given day
+    pub fn sync(&mut self, total: Duration, day: NaiveDate) {
+        if total > self.logged {
+            *self.days.entry(day).or_default() += total - self.logged;
+        } else if total < self.logged {
+            // Time taken back off the timer, like discarded idle time
+            let entry = self.days.entry(day).or_default();
+            *entry = entry.saturating_sub(self.logged - total);
+        }
+        self.logged = total;
+    }
+
+    // Starts counting from a new total without touching what was already booked, used when timers are reset
+    pub fn rebase(&mut self, total: Duration) {
+        self.logged = total;
+    }
+
+    pub fn on(&self, day: NaiveDate) -> Duration {
+        self.days.get(&day).copied().unwrap_or_default()
+    }
+
+    pub fn between(&self, start: NaiveDate, end: NaiveDate) -> Duration {
+        self.days.range(start..=end).map(|(_, time)| *time).sum()
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format_duration(self.time_from + self.append))
+    }
 }
 
-fn format_duration(duration: Duration) -> String {
+pub fn format_duration(duration: Duration) -> String {
     let secs = duration.as_secs();
     let hours = secs / 3600;
     let minutes = (secs % 3600) / 60;